@@ -0,0 +1,202 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::prelude::*;
+use afbv4::prelude::*;
+use display_lvgl_gui::prelude::*;
+use serde::Deserialize;
+use std::sync::{Mutex, MutexGuard};
+
+// binding config 'accessibility' object, 'widgets' lists enlarged labels (empty means every label)
+#[derive(Deserialize, Debug, Clone)]
+pub struct AccessConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_theme")]
+    pub theme: String,
+    #[serde(default = "default_font")]
+    pub font: String,
+    #[serde(default)]
+    pub widgets: Vec<String>,
+    #[serde(default)]
+    pub trigger: Option<String>,
+}
+
+fn default_theme() -> String {
+    "accessible".to_string()
+}
+
+fn default_font() -> String {
+    "std-22".to_string()
+}
+
+impl Default for AccessConfig {
+    fn default() -> Self {
+        AccessConfig {
+            enabled: false,
+            theme: default_theme(),
+            font: default_font(),
+            widgets: Vec::new(),
+            trigger: None,
+        }
+    }
+}
+
+impl AccessConfig {
+    pub fn from_jsonc(jaccess: JsoncObj) -> Result<Self, AfbError> {
+        match serde_json::from_str::<AccessConfig>(jaccess.to_string().as_str()) {
+            Ok(value) => Ok(value),
+            Err(error) => afb_error!("access-config-fail", "invalid accessibility config:{}", error),
+        }
+    }
+}
+
+pub struct AccessData {
+    enabled: bool,
+    labels: Vec<(&'static LvglLabel, &'static LvglFont)>,
+    icons: Option<&'static StatusIcons>,
+}
+
+// accessibility mode: pinned high contrast theme, larger fonts and status text with safe tints
+pub struct AccessHandle {
+    theme_name: String,
+    font: &'static LvglFont,
+    widgets: Vec<String>,
+    trigger: Option<String>,
+    theme: &'static ThemeHandle,
+    fonts: &'static FontCatalog,
+    data: Mutex<AccessData>,
+    dispatch: &'static LvglDispatch,
+}
+
+impl AccessHandle {
+    pub fn new(
+        config: AccessConfig,
+        theme: &'static ThemeHandle,
+        fonts: &'static FontCatalog,
+        dispatch: &'static LvglDispatch,
+    ) -> Result<&'static Self, AfbError> {
+        if !theme.has_theme(config.theme.as_str()) {
+            return afb_error!("access-config-fail", "unknown accessibility theme:'{}'", config.theme);
+        }
+        let handle = AccessHandle {
+            theme_name: config.theme,
+            font: fonts.get_font(config.font.as_str())?,
+            widgets: config.widgets,
+            trigger: config.trigger,
+            theme,
+            fonts,
+            data: Mutex::new(AccessData {
+                enabled: config.enabled,
+                labels: Vec::new(),
+                icons: None,
+            }),
+            dispatch,
+        };
+        Ok(Box::leak(Box::new(handle)))
+    }
+
+    pub fn get_state(&self) -> Result<MutexGuard<'_, AccessData>, AfbError> {
+        match self.data.lock() {
+            Ok(value) => Ok(value),
+            Err(_) => afb_error!("access-state-lock", "fail to lock accessibility state"),
+        }
+    }
+
+    // attach labels once panel is drawn and before lvgl loop starts, normal font is the configured or creation one
+    pub fn bind_labels(&'static self, display: &DisplayHandle) -> Result<(), AfbError> {
+        for uid in &self.widgets {
+            get_widget::<LvglLabel>(display, uid.as_str())?;
+        }
+        {
+            let mut data = self.get_state()?;
+            for widget in display.get_panel() {
+                let uid = widget.get_uid();
+                if !self.widgets.is_empty() && !self.widgets.iter().any(|value| value == uid) {
+                    continue;
+                }
+                if let Some(label) = widget.as_any().downcast_ref::<LvglLabel>() {
+                    let font = match self.fonts.get_widget_font(uid) {
+                        Some(font) => font,
+                        None => label.get_font(),
+                    };
+                    data.labels.push((label, font));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // status icons are created once lvgl loop is started, saved mode is applied from here
+    pub fn set_icons(&'static self, icons: &'static StatusIcons) -> Result<(), AfbError> {
+        self.get_state()?.icons = Some(icons);
+        if self.is_enabled()? {
+            self.apply(true)?;
+        }
+        Ok(())
+    }
+
+    pub fn is_enabled(&self) -> Result<bool, AfbError> {
+        Ok(self.get_state()?.enabled)
+    }
+
+    // long press widget, None leaves the mode to the verb only
+    pub fn get_trigger(&self) -> Option<&str> {
+        self.trigger.as_deref()
+    }
+
+    pub fn is_trigger(&self, uid: &str) -> bool {
+        self.trigger.as_deref() == Some(uid)
+    }
+
+    pub fn set_enabled(&'static self, enabled: bool) -> Result<(), AfbError> {
+        {
+            let mut data = self.get_state()?;
+            if data.enabled == enabled {
+                return Ok(());
+            }
+            data.enabled = enabled;
+        }
+        self.apply(enabled)
+    }
+
+    pub fn toggle(&'static self) -> Result<(), AfbError> {
+        let enabled = self.is_enabled()?;
+        self.set_enabled(!enabled)
+    }
+
+    fn apply(&'static self, enabled: bool) -> Result<(), AfbError> {
+        let data = self.get_state()?;
+        if enabled {
+            self.theme.force_theme(Some(self.theme_name.as_str()))?;
+        } else {
+            self.theme.force_theme(None)?;
+        }
+        if let Some(icons) = data.icons {
+            icons.set_access(enabled);
+        }
+
+        let large = self.font;
+        let labels = data.labels.clone();
+        self.dispatch.post(move || {
+            for (label, font) in &labels {
+                label.set_font(if enabled { large } else { *font });
+            }
+        });
+        Ok(())
+    }
+}
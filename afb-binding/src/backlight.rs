@@ -0,0 +1,376 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::prelude::*;
+use afbv4::prelude::*;
+use display_lvgl_gui::prelude::*;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+// binding config 'backlight' object, delays in seconds (0 disables), levels in percent of max_brightness
+#[derive(Deserialize, Debug, Clone)]
+pub struct BacklightConfig {
+    #[serde(default)]
+    pub dir: Option<String>,
+    #[serde(default)]
+    pub dim_delay: u32,
+    #[serde(default)]
+    pub saver_delay: u32,
+    #[serde(default = "default_dim_level")]
+    pub dim_level: u32,
+    #[serde(default)]
+    pub saver_level: u32,
+    #[serde(default = "default_full_level")]
+    pub full_level: u32,
+}
+
+fn default_dim_level() -> u32 {
+    30
+}
+
+fn default_full_level() -> u32 {
+    100
+}
+
+impl Default for BacklightConfig {
+    fn default() -> Self {
+        BacklightConfig {
+            dir: None,
+            dim_delay: 0,
+            saver_delay: 0,
+            dim_level: default_dim_level(),
+            saver_level: 0,
+            full_level: default_full_level(),
+        }
+    }
+}
+
+impl BacklightConfig {
+    pub fn from_jsonc(jbacklight: JsoncObj) -> Result<Self, AfbError> {
+        match serde_json::from_str::<BacklightConfig>(jbacklight.to_string().as_str()) {
+            Ok(value) => Ok(value),
+            Err(error) => afb_error!("backlight-config-fail", "invalid backlight config:{}", error),
+        }
+    }
+}
+
+// sysfs backlight device, 'dir' is either the device or its class (first device is used)
+struct BacklightDev {
+    brightness: PathBuf,
+    max: u32,
+}
+
+impl BacklightDev {
+    fn open(dir: &str) -> Result<Self, AfbError> {
+        let mut device = PathBuf::from(dir);
+        if !device.join("max_brightness").exists() {
+            let first = fs::read_dir(dir).ok().and_then(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.path())
+                    .find(|path| path.join("max_brightness").exists())
+            });
+            device = match first {
+                Some(value) => value,
+                None => return afb_error!("backlight-dev-fail", "no backlight device within:{}", dir),
+            };
+        }
+        let max = match read_value(&device.join("max_brightness")) {
+            Some(value) if value > 0 => value,
+            _ => return afb_error!("backlight-dev-fail", "invalid max_brightness within:{}", device.display()),
+        };
+        Ok(BacklightDev {
+            brightness: device.join("brightness"),
+            max,
+        })
+    }
+
+    fn set_level(&self, percent: u32) {
+        let value = self.max * percent.min(100) / 100;
+        if let Err(error) = fs::write(&self.brightness, value.to_string()) {
+            afb_log_msg!(Warning, None, "fail to write {}:{}", self.brightness.display(), error);
+        }
+    }
+}
+
+fn read_value(path: &Path) -> Option<u32> {
+    fs::read_to_string(path).ok().and_then(|value| value.trim().parse::<u32>().ok())
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ScreenState {
+    Active,
+    Dimmed,
+    Saver,
+    // saver drawn and lvgl redraw paused
+    Sleeping,
+}
+
+pub struct BacklightData {
+    state: ScreenState,
+    activity: Duration,
+    since: Duration,
+}
+
+// action decided by tick, kept apart from widgets and sysfs
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum BacklightStep {
+    Keep,
+    Wake,
+    Dim,
+    Sleep,
+    Pause,
+}
+
+impl BacklightData {
+    // touch is lvgl inactive time, activity the last wake() call
+    fn next_step(&self, config: &BacklightConfig, now: Duration, touch: Duration) -> BacklightStep {
+        let idle = now.saturating_sub(self.activity).min(touch);
+        let elapsed = now.saturating_sub(self.since);
+
+        // any touch after entering dimmed/saver state wakes the screen
+        if self.state != ScreenState::Active && touch < elapsed {
+            return BacklightStep::Wake;
+        }
+
+        let dim_delay = Duration::from_secs(config.dim_delay as u64);
+        let saver_delay = Duration::from_secs(config.saver_delay as u64);
+        match self.state {
+            ScreenState::Active | ScreenState::Dimmed if config.saver_delay > 0 && idle >= saver_delay => {
+                BacklightStep::Sleep
+            }
+            ScreenState::Active if config.dim_delay > 0 && idle >= dim_delay => BacklightStep::Dim,
+            // saver was drawn during previous tick
+            ScreenState::Saver => BacklightStep::Pause,
+            _ => BacklightStep::Keep,
+        }
+    }
+}
+
+// inactivity dimming and screensaver, touch is read from lvgl, other wake sources call wake()
+pub struct BacklightHandle {
+    device: Option<BacklightDev>,
+    config: BacklightConfig,
+    overlay: &'static LvglOverlay,
+    data_set: Mutex<BacklightData>,
+    dispatch: &'static LvglDispatch,
+}
+
+impl BacklightHandle {
+    pub fn new(display: &DisplayHandle, config: BacklightConfig) -> Result<&'static Self, AfbError> {
+        // a board without backlight control still gets the screensaver
+        let device = match &config.dir {
+            Some(dir) => match BacklightDev::open(dir.as_str()) {
+                Ok(value) => Some(value),
+                Err(error) => {
+                    afb_log_msg!(Warning, None, "backlight disabled:{}", error);
+                    None
+                }
+            },
+            None => None,
+        };
+        let now = time_now();
+        let handle = BacklightHandle {
+            device,
+            config,
+            overlay: get_widget::<LvglOverlay>(display, "Overlay-saver")?,
+            data_set: Mutex::new(BacklightData {
+                state: ScreenState::Active,
+                activity: now,
+                since: now,
+            }),
+            dispatch: display.get_dispatch(),
+        };
+        handle.set_level(handle.config.full_level);
+        Ok(Box::leak(Box::new(handle)))
+    }
+
+    fn get_state(&self) -> Result<MutexGuard<'_, BacklightData>, AfbError> {
+        match self.data_set.lock() {
+            Ok(value) => Ok(value),
+            Err(_) => afb_error!("backlight-state-lock", "fail to lock backlight state"),
+        }
+    }
+
+    fn set_level(&self, percent: u32) {
+        if let Some(device) = &self.device {
+            device.set_level(percent);
+        }
+    }
+
+    // plug-in, badge, verb or touch
+    pub fn wake(&'static self) -> Result<(), AfbError> {
+        let mut data = self.get_state()?;
+        let now = time_now();
+        data.activity = now;
+        if data.state == ScreenState::Active {
+            return Ok(());
+        }
+        data.state = ScreenState::Active;
+        data.since = now;
+        drop(data);
+
+        self.set_level(self.config.full_level);
+        self.dispatch.post(move || {
+            DisplayHandle::set_refresh_pause(false);
+            self.overlay.set_hidden(true);
+        });
+        Ok(())
+    }
+
+    // forced from verb or after saver delay
+    pub fn sleep(&'static self) -> Result<(), AfbError> {
+        let mut data = self.get_state()?;
+        if matches!(data.state, ScreenState::Saver | ScreenState::Sleeping) {
+            return Ok(());
+        }
+        data.state = ScreenState::Saver;
+        data.since = time_now();
+        drop(data);
+
+        self.set_level(self.config.saver_level);
+        self.dispatch.post(move || {
+            self.overlay.set_hidden(false);
+        });
+        Ok(())
+    }
+
+    fn dim(&'static self) -> Result<(), AfbError> {
+        let mut data = self.get_state()?;
+        data.state = ScreenState::Dimmed;
+        data.since = time_now();
+        drop(data);
+        self.set_level(self.config.dim_level);
+        Ok(())
+    }
+
+    // called from backlight timer
+    pub fn tick(&'static self) -> Result<(), AfbError> {
+        let touch = Duration::from_millis(DisplayHandle::get_inactive_time() as u64);
+        let step = self.get_state()?.next_step(&self.config, time_now(), touch);
+        match step {
+            BacklightStep::Wake => self.wake()?,
+            BacklightStep::Sleep => self.sleep()?,
+            BacklightStep::Dim => self.dim()?,
+            BacklightStep::Pause => {
+                self.get_state()?.state = ScreenState::Sleeping;
+                self.dispatch.post(|| DisplayHandle::set_refresh_pause(true));
+            }
+            BacklightStep::Keep => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // fake sysfs device under temp dir, removed by caller
+    fn fake_device(name: &str, max: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("backlight-{}-{}", std::process::id(), name));
+        let device = dir.join("panel0");
+        fs::create_dir_all(&device).unwrap();
+        fs::write(device.join("max_brightness"), max).unwrap();
+        fs::write(device.join("brightness"), "0").unwrap();
+        dir
+    }
+
+    fn config(dim_delay: u32, saver_delay: u32) -> BacklightConfig {
+        BacklightConfig {
+            dim_delay,
+            saver_delay,
+            ..BacklightConfig::default()
+        }
+    }
+
+    fn data(state: ScreenState, activity: u64, since: u64) -> BacklightData {
+        BacklightData {
+            state,
+            activity: Duration::from_secs(activity),
+            since: Duration::from_secs(since),
+        }
+    }
+
+    #[test]
+    fn device_from_class_and_levels() {
+        let dir = fake_device("class", "200\n");
+        let dev = BacklightDev::open(dir.to_str().unwrap()).unwrap();
+        assert_eq!(dev.max, 200);
+
+        dev.set_level(30);
+        assert_eq!(read_value(&dev.brightness), Some(60));
+        dev.set_level(150);
+        assert_eq!(read_value(&dev.brightness), Some(200));
+
+        // device directory given directly
+        let direct = BacklightDev::open(dir.join("panel0").to_str().unwrap()).unwrap();
+        assert_eq!(direct.brightness, dev.brightness);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn device_missing_or_invalid() {
+        let dir = fake_device("invalid", "0");
+        assert!(BacklightDev::open(dir.to_str().unwrap()).is_err());
+        assert!(BacklightDev::open(dir.join("none").to_str().unwrap()).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn active_dims_then_saver() {
+        let config = config(30, 120);
+        let touch = Duration::from_secs(3600);
+        let active = data(ScreenState::Active, 1000, 1000);
+        assert_eq!(active.next_step(&config, Duration::from_secs(1020), touch), BacklightStep::Keep);
+        assert_eq!(active.next_step(&config, Duration::from_secs(1030), touch), BacklightStep::Dim);
+
+        let dimmed = data(ScreenState::Dimmed, 1000, 1030);
+        assert_eq!(dimmed.next_step(&config, Duration::from_secs(1100), touch), BacklightStep::Keep);
+        assert_eq!(dimmed.next_step(&config, Duration::from_secs(1120), touch), BacklightStep::Sleep);
+
+        let saver = data(ScreenState::Saver, 1000, 1120);
+        assert_eq!(saver.next_step(&config, Duration::from_secs(1121), touch), BacklightStep::Pause);
+        let sleeping = data(ScreenState::Sleeping, 1000, 1120);
+        assert_eq!(sleeping.next_step(&config, Duration::from_secs(2000), touch), BacklightStep::Keep);
+    }
+
+    #[test]
+    fn recent_touch_holds_and_wakes() {
+        let config = config(30, 120);
+        // screen touched 5s ago, wake() not called for long
+        let touch = Duration::from_secs(5);
+        let active = data(ScreenState::Active, 1000, 1000);
+        assert_eq!(active.next_step(&config, Duration::from_secs(1200), touch), BacklightStep::Keep);
+
+        let sleeping = data(ScreenState::Sleeping, 1000, 1120);
+        assert_eq!(sleeping.next_step(&config, Duration::from_secs(1200), touch), BacklightStep::Wake);
+        let dimmed = data(ScreenState::Dimmed, 1000, 1030);
+        assert_eq!(dimmed.next_step(&config, Duration::from_secs(1060), touch), BacklightStep::Wake);
+    }
+
+    #[test]
+    fn zero_delays_disable() {
+        let config = config(0, 0);
+        let active = data(ScreenState::Active, 0, 0);
+        let now = Duration::from_secs(100_000);
+        assert_eq!(active.next_step(&config, now, now), BacklightStep::Keep);
+    }
+}
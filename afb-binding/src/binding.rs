@@ -37,12 +37,32 @@ pub(crate) enum QueryOnOff {
     OFF,
 }
 
-fn json_to_color(jcolor: JsoncObj) -> Result<LvglColor, AfbError> {
-    let red = jcolor.get::<u32>("red")?;
-    let blue = jcolor.get::<u32>("blue")?;
-    let green = jcolor.get::<u32>("green")?;
+AfbDataConverter!(api_arg_asset, QueryAsset);
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "action")]
+pub(crate) enum QueryAsset {
+    LIST,
+    SET { uid: String, asset: String },
+}
+
+AfbDataConverter!(api_arg_theme, QueryTheme);
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "action")]
+pub(crate) enum QueryTheme {
+    LIST,
+    SET { name: String },
+}
 
-    Ok(LvglColor::rvb(red as u8, green as u8, blue as u8))
+// typed nfc reader state from dbus, events that do not parse show reader as off and never wake the screen
+AfbDataConverter!(nfc_reader_msg, NfcReaderMsg);
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum NfcReaderMsg {
+    Present,
+    Absent,
+    Detected,
+    Removed,
+    Error,
 }
 
 pub struct ApiConfig {
@@ -52,13 +72,38 @@ pub struct ApiConfig {
     pub dbus_api: &'static str,
 }
 
+// optional grid meter (Linky) energy manager verbs
+#[derive(Clone, Copy)]
+pub struct GridConfig {
+    pub avail_verb: &'static str,
+    pub over_verb: &'static str,
+}
+
+// optional display features config
+pub struct PanelConfig {
+    pub faults: FaultConfig,
+    pub evse_imax: u32,
+    pub cable_imax: u32,
+    pub grid: GridConfig,
+    pub freshness: FreshnessConfig,
+    pub refresh_rate: u32,
+    pub qrcode: QrcodeConfig,
+    pub network: NetworkConfig,
+    pub fonts: &'static FontCatalog,
+    pub icons: IconConfig,
+    pub access: &'static AccessHandle,
+    pub backlight: BacklightConfig,
+    pub carousel: CarouselConfig,
+}
+
 // wait until both apis (iso+slac) to be ready before trying event subscription
 struct ApiUserData {
     engy_api: &'static str,
     chmgr_api: &'static str,
     auth_api: &'static str,
     dbus_api: &'static str,
-    auth_widget: &'static  LvglPixmap,
+    panels: PanelHandles,
+    grid: GridConfig,
 }
 
 impl AfbApiControls for ApiUserData {
@@ -79,11 +124,13 @@ impl AfbApiControls for ApiUserData {
         AfbSubCall::call_sync(api, self.engy_api, "energy", "{'action':'subscribe'}")?;
         AfbSubCall::call_sync(api, self.engy_api, "current", "{'action':'subscribe'}")?;
         AfbSubCall::call_sync(api, self.engy_api, "power", "{'action':'subscribe'}")?;
-        /*Should be remove if unused
-        if let Err(_msg_error) = AfbSubCall::call_sync(api, self.engy_api, "adsp", "{'action':'subscribe'}") {
-            afb_log_msg!(Warning, api, "subscribing To adsp failed, linky missing");
+
+        // grid meter is optional, display keeps '--' when linky is missing
+        for verb in [self.grid.avail_verb, self.grid.over_verb] {
+            if let Err(_msg_error) = AfbSubCall::call_sync(api, self.engy_api, verb, "{'action':'subscribe'}") {
+                afb_log_msg!(Warning, api, "subscribing To {} failed, linky missing", verb);
+            }
         }
-        */
 
         AfbSubCall::call_sync(api, self.auth_api, "subscribe", true)?;
         AfbSubCall::call_sync(api, self.chmgr_api, "subscribe", true)?;
@@ -92,7 +139,7 @@ impl AfbApiControls for ApiUserData {
 
         let api_config = ApiConfig{ engy_api:self.engy_api , chmgr_api:self.chmgr_api, auth_api:self.auth_api, dbus_api:self.dbus_api};
 
-        init_display_value(api, self.auth_widget, api_config)?;
+        init_display_value(api, api_config, self.panels)?;
 
         afb_log_msg!(Notice, api, "subscribing charging_api done ");
 
@@ -111,6 +158,9 @@ pub fn binding_init(rootv4: AfbApiV4, jconf: JsoncObj) -> Result<&'static AfbApi
     // add binding custom converter
     api_arg_subscribe::register()?;
     api_arg_switch::register()?;
+    api_arg_asset::register()?;
+    api_arg_theme::register()?;
+    nfc_reader_msg::register()?;
 
     // add binding custom converter
     engy_registers()?;
@@ -166,19 +216,37 @@ pub fn binding_init(rootv4: AfbApiV4, jconf: JsoncObj) -> Result<&'static AfbApi
         }
     };
 
-    // check theme and provide default if needed
-    if let Ok(jvalue) = jconf.get::<JsoncObj>("theme") {
-        let dark = jvalue.get::<bool>("dark")?;
-        let primary = json_to_color(jvalue.get::<JsoncObj>("primary")?)?;
-        let secondary = json_to_color(jvalue.get::<JsoncObj>("secondary")?)?;
-        display.set_theme(primary, secondary, dark, LvglMkFont::std_14());
-    } else {
-        let primary = LvglColor::LIGHT_BLUE();
-        let secondary = LvglColor::BLUE_GREY();
-        // Fulup TBD apply a correct theme
-        display.set_theme(primary, secondary, false, LvglMkFont::std_14());
+    // runtime images (png or lvgl bin) override compiled assets with the same name
+    if let Ok(value) = jconf.get::<String>("assets") {
+        match AssetPixmap::load_dir(value.as_str()) {
+            Ok(report) => {
+                afb_log_msg!(Notice, rootv4, "runtime assets loaded:{:?}", report.loaded);
+                for path in report.failed {
+                    afb_log_msg!(Warning, rootv4, "fail to decode asset:{}", path);
+                }
+            }
+            Err(error) => afb_log_msg!(Warning, rootv4, "compiled assets only: {}", error),
+        }
     }
 
+    // runtime fonts, builtin montserrat when not configured
+    let fonts = match jconf.get::<JsoncObj>("fonts") {
+        Ok(jvalue) => FontCatalog::new(FontConfig::from_jsonc(jvalue)?)?,
+        Err(_) => FontCatalog::new(FontConfig::default())?,
+    };
+
+    // named themes, legacy single 'theme' object or light-blue default
+    let themes = match jconf.get::<JsoncObj>("themes") {
+        Ok(jvalue) => ThemeConfig::from_jsonc(jvalue)?,
+        Err(_) => match jconf.get::<JsoncObj>("theme") {
+            Ok(jvalue) => ThemeConfig::from_legacy(jvalue)?,
+            Err(_) => ThemeConfig::default(),
+        },
+    };
+    let dispatch = display.get_dispatch();
+    let theme = ThemeHandle::new(themes, fonts, dispatch)?;
+    theme.init(&mut display)?;
+
     let engy_api = if let Ok(value) = jconf.get::<String>("engy_api") {
         to_static_str(value)
     } else {
@@ -218,6 +286,132 @@ pub fn binding_init(rootv4: AfbApiV4, jconf: JsoncObj) -> Result<&'static AfbApi
     };
 
     let api_config = ApiConfig { engy_api , chmgr_api, auth_api, dbus_api};
+
+    // optional session cost computation
+    let tariff = match jconf.get::<JsoncObj>("tariff") {
+        Ok(jvalue) => Some(Tariff::from_jsonc(jvalue)?),
+        Err(_) => None,
+    };
+
+    // end of session summary stays 30s after plug-out by default
+    let summary_timeout = if let Ok(value) = jconf.get::<u32>("summary_timeout") {
+        value
+    } else {
+        30
+    };
+
+    // session power curve rolling window (default 15mn)
+    let session_config = match jconf.get::<JsoncObj>("chart") {
+        Ok(jvalue) => SessionConfig {
+            window: jvalue.get::<u32>("window")?,
+            points: jvalue.get::<u32>("points")?,
+            tariff,
+            summary_timeout,
+        },
+        Err(_) => SessionConfig {
+            window: 900,
+            points: 180,
+            tariff,
+            summary_timeout,
+        },
+    };
+    let session = SessionHandle::new(session_config);
+
+    // optional fault support link and message translation
+    let faults = match jconf.get::<JsoncObj>("faults") {
+        Ok(jvalue) => FaultConfig::from_jsonc(jvalue)?,
+        Err(_) => FaultConfig::default(),
+    };
+
+    // EVSE max is updated from charging manager state, cable max only comes from config
+    let evse_imax = if let Ok(value) = jconf.get::<u32>("evse_imax") {
+        value
+    } else {
+        32
+    };
+
+    let cable_imax = if let Ok(value) = jconf.get::<u32>("cable_imax") {
+        value
+    } else {
+        evse_imax
+    };
+
+    let grid = match jconf.get::<JsoncObj>("grid") {
+        Ok(jvalue) => GridConfig {
+            avail_verb: to_static_str(jvalue.get::<String>("avail")?),
+            over_verb: to_static_str(jvalue.get::<String>("over")?),
+        },
+        Err(_) => GridConfig {
+            avail_verb: "iavail",
+            over_verb: "adsp",
+        },
+    };
+
+    // optional stale value watchdog, disabled by default
+    let freshness = match jconf.get::<JsoncObj>("freshness") {
+        Ok(jvalue) => FreshnessConfig::from_jsonc(jvalue)?,
+        Err(_) => FreshnessConfig::default(),
+    };
+
+    // max meter label and chart redraw per second
+    let refresh_rate = if let Ok(value) = jconf.get::<u32>("refresh_rate") {
+        value
+    } else {
+        5
+    };
+    if !(1..=50).contains(&refresh_rate) {
+        return afb_error!(
+            "binding-refresh-rate-config",
+            "refresh_rate:{} SHOULD be within 1..50",
+            refresh_rate
+        );
+    }
+
+    // qrcode payload template, station and connector ids
+    let qrcode = match jconf.get::<JsoncObj>("qrcode") {
+        Ok(jvalue) => QrcodeConfig::from_jsonc(jvalue)?,
+        Err(_) => QrcodeConfig::default(),
+    };
+
+    // network interfaces used for menu icons
+    let network = match jconf.get::<JsoncObj>("network") {
+        Ok(jvalue) => NetworkConfig::from_jsonc(jvalue)?,
+        Err(_) => NetworkConfig::default(),
+    };
+
+    // state -> icon tables, missing states keep builtin assets
+    let icons = match jconf.get::<JsoncObj>("icons") {
+        Ok(jvalue) => IconConfig::from_jsonc(jvalue)?,
+        Err(_) => IconConfig::default(),
+    };
+
+    // translation catalogs, english only by default
+    let i18n = match jconf.get::<JsoncObj>("i18n") {
+        Ok(jvalue) => LocaleConfig::from_jsonc(jvalue)?,
+        Err(_) => LocaleConfig::default(),
+    };
+    let locale = LocaleHandle::new(i18n, dispatch)?;
+
+    // accessibility mode, toggled by long press or verb
+    let access = match jconf.get::<JsoncObj>("accessibility") {
+        Ok(jvalue) => AccessConfig::from_jsonc(jvalue)?,
+        Err(_) => AccessConfig::default(),
+    };
+    let access = AccessHandle::new(access, theme, fonts, dispatch)?;
+
+    // inactivity dimming and screensaver, disabled by default
+    let backlight = match jconf.get::<JsoncObj>("backlight") {
+        Ok(jvalue) => BacklightConfig::from_jsonc(jvalue)?,
+        Err(_) => BacklightConfig::default(),
+    };
+
+    // idle slides over top area, disabled without slides
+    let carousel = match jconf.get::<JsoncObj>("carousel") {
+        Ok(jvalue) => CarouselConfig::from_jsonc(jvalue)?,
+        Err(_) => CarouselConfig::default(),
+    };
+
+    let panel_config = PanelConfig { faults, evse_imax, cable_imax, grid, freshness, refresh_rate, qrcode, network, fonts, icons, access, backlight, carousel };
     
     // create backend API
     // --------------------------------------------------------
@@ -225,21 +419,10 @@ pub fn binding_init(rootv4: AfbApiV4, jconf: JsoncObj) -> Result<&'static AfbApi
         .set_info(info)
         .set_permission(permission);
 
-    register_verbs(api, &mut display, api_config)?;
-
-    let auth_widget = match display.get_by_uid("Pixmap-auth-status").downcast_ref::<LvglPixmap>() {
-        Some(auth_widget) => auth_widget,
-        None => {
-            return afb_error!(
-                "verb-info-widget",
-                "no widget uid:{} type:{} found in panel",
-                "Pixmap-auth-status",
-                "LvglPixmap"
-            )
-        }
-    };
+    let panels = register_verbs(api, &mut display, api_config, session, locale, panel_config)?;
+    register_theme(api, theme)?;
 
-    api.set_callback(Box::new(ApiUserData { engy_api, chmgr_api, auth_api, dbus_api, auth_widget}));
+    api.set_callback(Box::new(ApiUserData { engy_api, chmgr_api, auth_api, dbus_api, panels, grid}));
     
     api.require_api(engy_api);
     api.require_api(chmgr_api);
@@ -0,0 +1,251 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::prelude::*;
+use afbv4::prelude::*;
+use display_lvgl_gui::prelude::*;
+use serde::Deserialize;
+use std::fs;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+// one slide, either a named asset or an image file within carousel 'dir'
+#[derive(Deserialize, Debug, Clone)]
+pub struct SlideEntry {
+    #[serde(default)]
+    pub asset: Option<String>,
+    #[serde(default)]
+    pub file: Option<String>,
+    #[serde(default)]
+    pub duration: Option<u32>,
+}
+
+// binding config 'carousel' object, without 'slides' every png/bin within 'dir' is shown in name order
+#[derive(Deserialize, Debug, Clone)]
+pub struct CarouselConfig {
+    #[serde(default)]
+    pub dir: Option<String>,
+    #[serde(default)]
+    pub slides: Vec<SlideEntry>,
+    #[serde(default = "default_duration")]
+    pub duration: u32,
+    #[serde(default = "default_idle_delay")]
+    pub idle_delay: u32,
+}
+
+fn default_duration() -> u32 {
+    8
+}
+
+fn default_idle_delay() -> u32 {
+    120
+}
+
+impl Default for CarouselConfig {
+    fn default() -> Self {
+        CarouselConfig {
+            dir: None,
+            slides: Vec::new(),
+            duration: default_duration(),
+            idle_delay: default_idle_delay(),
+        }
+    }
+}
+
+impl CarouselConfig {
+    pub fn from_jsonc(jcarousel: JsoncObj) -> Result<Self, AfbError> {
+        match serde_json::from_str::<CarouselConfig>(jcarousel.to_string().as_str()) {
+            Ok(value) => Ok(value),
+            Err(error) => afb_error!("carousel-config-fail", "invalid carousel config:{}", error),
+        }
+    }
+
+    fn get_path(&self, file: &str) -> String {
+        match &self.dir {
+            Some(dir) if !file.starts_with('/') => format!("{}/{}", dir, file),
+            _ => file.to_string(),
+        }
+    }
+
+    // a broken promotional image should not prevent display from starting
+    fn load_file(path: &str) -> Option<&'static LvglImgDsc> {
+        let img = LvglImgFile::load(path);
+        if img.is_none() {
+            afb_log_msg!(Warning, None, "fail to load carousel slide:{}", path);
+        }
+        img
+    }
+
+    fn load_slides(&self) -> Result<Vec<(&'static LvglImgDsc, Duration)>, AfbError> {
+        let mut slides = Vec::new();
+        for slide in &self.slides {
+            let img = match (&slide.asset, &slide.file) {
+                (Some(asset), None) => match AssetPixmap::by_name(asset.as_str()) {
+                    Some(img) => img,
+                    None => return afb_error!("carousel-config-fail", "unknown slide asset:{}", asset),
+                },
+                (None, Some(file)) => match Self::load_file(self.get_path(file).as_str()) {
+                    Some(img) => img,
+                    None => continue,
+                },
+                _ => return afb_error!("carousel-config-fail", "slide should define either 'asset' or 'file'"),
+            };
+            let duration = slide.duration.unwrap_or(self.duration);
+            slides.push((img, Duration::from_secs(duration.max(1) as u64)));
+        }
+
+        if let (true, Some(dir)) = (self.slides.is_empty(), &self.dir) {
+            let mut files: Vec<String> = match fs::read_dir(dir) {
+                Ok(entries) => entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| matches!(path.extension(), Some(ext) if ext == "png" || ext == "bin"))
+                    .map(|path| path.to_string_lossy().to_string())
+                    .collect(),
+                Err(error) => {
+                    afb_log_msg!(Warning, None, "fail to read carousel dir:{} error:{}", dir, error);
+                    Vec::new()
+                }
+            };
+            files.sort();
+            for path in files {
+                if let Some(img) = Self::load_file(path.as_str()) {
+                    slides.push((img, Duration::from_secs(self.duration.max(1) as u64)));
+                }
+            }
+        }
+        Ok(slides)
+    }
+}
+
+pub struct CarouselData {
+    shown: bool,
+    busy: bool,
+    index: usize,
+    activity: Duration,
+    since: Duration,
+    next: Duration,
+}
+
+// idle carousel over top area, stopped by plug-in, badge or touch and restarted after idle delay
+pub struct CarouselHandle {
+    slides: Vec<(&'static LvglImgDsc, Duration)>,
+    idle_delay: Duration,
+    overlay: &'static LvglOverlay,
+    pixmap: &'static LvglPixmap,
+    data_set: Mutex<CarouselData>,
+    dispatch: &'static LvglDispatch,
+}
+
+impl CarouselHandle {
+    pub fn new(display: &DisplayHandle, config: CarouselConfig) -> Result<&'static Self, AfbError> {
+        let now = time_now();
+        let carousel = CarouselHandle {
+            slides: config.load_slides()?,
+            idle_delay: Duration::from_secs(config.idle_delay as u64),
+            overlay: get_widget::<LvglOverlay>(display, "Overlay-carousel")?,
+            pixmap: get_widget::<LvglPixmap>(display, "Pixmap-carousel")?,
+            data_set: Mutex::new(CarouselData {
+                shown: false,
+                busy: false,
+                index: 0,
+                activity: now,
+                since: now,
+                next: now,
+            }),
+            dispatch: display.get_dispatch(),
+        };
+        Ok(Box::leak(Box::new(carousel)))
+    }
+
+    // press callbacks are registered on carousel area to stop it without waiting next tick
+    pub fn get_area(&self) -> &'static LvglWidget {
+        self.overlay.get_area()
+    }
+
+    fn get_state(&self) -> Result<MutexGuard<'_, CarouselData>, AfbError> {
+        match self.data_set.lock() {
+            Ok(value) => Ok(value),
+            Err(_) => afb_error!("carousel-state-lock", "fail to lock carousel state"),
+        }
+    }
+
+    fn show_slide(&'static self, data: &mut CarouselData, now: Duration) {
+        let (img, duration) = self.slides[data.index];
+        data.next = now + duration;
+        let first = !data.shown;
+        data.shown = true;
+        self.dispatch.post(move || {
+            self.pixmap.set_value(img);
+            if first {
+                self.overlay.set_hidden(false);
+            }
+        });
+    }
+
+    fn hide(&'static self, data: &mut CarouselData) {
+        if data.shown {
+            data.shown = false;
+            self.dispatch.post(move || {
+                self.overlay.set_hidden(true);
+            });
+        }
+    }
+
+    // badge, press or any user action, carousel restarts after idle delay
+    pub fn interrupt(&'static self) -> Result<(), AfbError> {
+        let mut data = self.get_state()?;
+        data.activity = time_now();
+        self.hide(&mut data);
+        Ok(())
+    }
+
+    // no carousel while a vehicle is plugged
+    pub fn set_busy(&'static self, busy: bool) -> Result<(), AfbError> {
+        self.get_state()?.busy = busy;
+        self.interrupt()
+    }
+
+    // called from carousel timer
+    pub fn tick(&'static self) -> Result<(), AfbError> {
+        if self.slides.is_empty() {
+            return Ok(());
+        }
+        let now = time_now();
+        let touch = Duration::from_millis(DisplayHandle::get_inactive_time() as u64);
+        let mut data = self.get_state()?;
+
+        if data.shown {
+            if touch < now.saturating_sub(data.since) {
+                data.activity = now;
+                self.hide(&mut data);
+            } else if now >= data.next {
+                data.index = (data.index + 1) % self.slides.len();
+                self.show_slide(&mut data, now);
+            }
+            return Ok(());
+        }
+
+        let idle = now.saturating_sub(data.activity).min(touch);
+        if !data.busy && idle >= self.idle_delay {
+            data.index = 0;
+            data.since = now;
+            self.show_slide(&mut data, now);
+        }
+        Ok(())
+    }
+}
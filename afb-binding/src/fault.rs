@@ -0,0 +1,230 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ */
+
+use afbv4::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use typesv4::prelude::*;
+
+// human readable fault description, texts are referenced by key to allow translation
+#[derive(Clone, Copy)]
+pub struct FaultInfo {
+    pub code: &'static str,
+    pub message: &'static str,
+    pub action: &'static str,
+    pub permanent: bool,
+}
+
+pub fn fault_info(error: &ErrorState) -> FaultInfo {
+    match error {
+        ErrorState::ErrE => FaultInfo {
+            code: "E01",
+            message: "fault-cp-msg",
+            action: "fault-replug-act",
+            permanent: false,
+        },
+        ErrorState::ErrDf => FaultInfo {
+            code: "E02",
+            message: "fault-diode-msg",
+            action: "fault-cable-act",
+            permanent: false,
+        },
+        ErrorState::ErrRelay => FaultInfo {
+            code: "E03",
+            message: "fault-relay-msg",
+            action: "fault-support-act",
+            permanent: true,
+        },
+        ErrorState::ErrRdc => FaultInfo {
+            code: "E04",
+            message: "fault-rcd-msg",
+            action: "fault-unplug-act",
+            permanent: false,
+        },
+        ErrorState::ErrOverCurrent => FaultInfo {
+            code: "E05",
+            message: "fault-overcurrent-msg",
+            action: "fault-retry-act",
+            permanent: false,
+        },
+        ErrorState::ErrVentilation => FaultInfo {
+            code: "E06",
+            message: "fault-ventilation-msg",
+            action: "fault-other-act",
+            permanent: false,
+        },
+        ErrorState::ErrPermanent => FaultInfo {
+            code: "E99",
+            message: "fault-permanent-msg",
+            action: "fault-support-act",
+            permanent: true,
+        },
+    }
+}
+
+// PlugState::Error does not carry any detail
+pub fn fault_plug_info() -> FaultInfo {
+    FaultInfo {
+        code: "E10",
+        message: "fault-plug-msg",
+        action: "fault-replug-act",
+        permanent: false,
+    }
+}
+
+// a permanent fault is never downgraded, only chmgr back to idle releases it
+pub struct FaultLatch {
+    permanent: AtomicBool,
+}
+
+impl FaultLatch {
+    pub fn new() -> Self {
+        FaultLatch {
+            permanent: AtomicBool::new(false),
+        }
+    }
+
+    // false when a recoverable fault comes under a permanent one and should not be shown
+    pub fn raise(&self, info: &FaultInfo) -> bool {
+        if info.permanent {
+            self.permanent.store(true, Ordering::Relaxed);
+            true
+        } else {
+            !self.is_permanent()
+        }
+    }
+
+    pub fn is_permanent(&self) -> bool {
+        self.permanent.load(Ordering::Relaxed)
+    }
+
+    pub fn release(&self) {
+        self.permanent.store(false, Ordering::Relaxed);
+    }
+}
+
+fn fault_default_text(key: &str) -> &'static str {
+    match key {
+        "fault-cp-msg" => "Vehicle communication error",
+        "fault-diode-msg" => "Vehicle diode fault detected",
+        "fault-relay-msg" => "Station power relay failure",
+        "fault-rcd-msg" => "Residual current detected",
+        "fault-overcurrent-msg" => "Vehicle exceeded allowed current",
+        "fault-ventilation-msg" => "Vehicle requests ventilation",
+        "fault-permanent-msg" => "Station out of order",
+        "fault-plug-msg" => "Charging cable error",
+        "fault-replug-act" => "Unplug the cable and plug it again",
+        "fault-cable-act" => "Check the cable and vehicle, then retry",
+        "fault-unplug-act" => "Unplug the vehicle, call support if it persists",
+        "fault-retry-act" => "Unplug, wait one minute and retry",
+        "fault-other-act" => "Please use another station",
+        "fault-support-act" => "Please use another station and contact support",
+        _ => "",
+    }
+}
+
+// binding config 'faults' object
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct FaultConfig {
+    // support link, '{code}' is replaced by fault code
+    #[serde(default)]
+    pub support: String,
+    // per key text override (localisation)
+    #[serde(default)]
+    pub texts: HashMap<String, String>,
+}
+
+impl FaultConfig {
+    pub fn from_jsonc(jfaults: JsoncObj) -> Result<Self, AfbError> {
+        match serde_json::from_str::<FaultConfig>(jfaults.to_string().as_str()) {
+            Ok(value) => Ok(value),
+            Err(error) => afb_error!("faults-config-fail", "invalid faults config:{}", error),
+        }
+    }
+
+    pub fn get_text(&self, key: &str) -> String {
+        match self.texts.get(key) {
+            Some(text) => text.clone(),
+            None => fault_default_text(key).to_string(),
+        }
+    }
+
+    pub fn get_support(&self, code: &str) -> String {
+        if self.support.is_empty() {
+            format!("EVSE-FAULT;code={}", code)
+        } else {
+            self.support.replace("{code}", code)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ERRORS: [ErrorState; 7] = [
+        ErrorState::ErrE,
+        ErrorState::ErrDf,
+        ErrorState::ErrRelay,
+        ErrorState::ErrRdc,
+        ErrorState::ErrOverCurrent,
+        ErrorState::ErrPermanent,
+        ErrorState::ErrVentilation,
+    ];
+
+    #[test]
+    fn fault_info_mapping() {
+        let expected = [
+            ("E01", "fault-cp-msg", "fault-replug-act", false),
+            ("E02", "fault-diode-msg", "fault-cable-act", false),
+            ("E03", "fault-relay-msg", "fault-support-act", true),
+            ("E04", "fault-rcd-msg", "fault-unplug-act", false),
+            ("E05", "fault-overcurrent-msg", "fault-retry-act", false),
+            ("E99", "fault-permanent-msg", "fault-support-act", true),
+            ("E06", "fault-ventilation-msg", "fault-other-act", false),
+        ];
+        for (error, (code, message, action, permanent)) in ERRORS.iter().zip(expected) {
+            let info = fault_info(error);
+            assert_eq!(info.code, code, "{:?}", error);
+            assert_eq!(info.message, message, "{:?}", error);
+            assert_eq!(info.action, action, "{:?}", error);
+            assert_eq!(info.permanent, permanent, "{:?}", error);
+        }
+    }
+
+    #[test]
+    fn fault_texts_have_defaults() {
+        let plug = fault_plug_info();
+        for info in ERRORS.iter().map(fault_info).chain([plug]) {
+            assert!(!fault_default_text(info.message).is_empty(), "{}", info.message);
+            assert!(!fault_default_text(info.action).is_empty(), "{}", info.action);
+        }
+    }
+
+    #[test]
+    fn permanent_fault_released_on_idle() {
+        let latch = FaultLatch::new();
+        assert!(latch.raise(&fault_plug_info()));
+        assert!(!latch.is_permanent());
+
+        assert!(latch.raise(&fault_info(&ErrorState::ErrRelay)));
+        assert!(latch.is_permanent());
+        // recoverable fault never hides a permanent one
+        assert!(!latch.raise(&fault_info(&ErrorState::ErrE)));
+
+        // PowerRequest::Idle
+        latch.release();
+        assert!(!latch.is_permanent());
+        assert!(latch.raise(&fault_info(&ErrorState::ErrE)));
+    }
+}
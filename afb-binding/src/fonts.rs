@@ -0,0 +1,177 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use afbv4::prelude::*;
+use display_lvgl_gui::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// one font file, 'fallback' names the face used for glyphs it does not cover
+#[derive(Deserialize, Debug, Clone)]
+pub struct FontFace {
+    pub uid: String,
+    pub file: String,
+    pub size: u16,
+    #[serde(default)]
+    pub fallback: Option<String>,
+}
+
+// binding config 'fonts' object, 'widgets' maps widget uid to face uid
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct FontConfig {
+    #[serde(default)]
+    pub dir: String,
+    #[serde(default)]
+    pub faces: Vec<FontFace>,
+    #[serde(default)]
+    pub theme: Option<String>,
+    #[serde(default)]
+    pub widgets: HashMap<String, String>,
+}
+
+impl FontConfig {
+    pub fn from_jsonc(jfonts: JsoncObj) -> Result<Self, AfbError> {
+        match serde_json::from_str::<FontConfig>(jfonts.to_string().as_str()) {
+            Ok(value) => Ok(value),
+            Err(error) => afb_error!("font-config-fail", "invalid fonts config:{}", error),
+        }
+    }
+}
+
+// builtin faces are always available and end every fallback chain
+const BUILTIN_FACES: [(&str, u16); 3] = [("std-14", 14), ("std-18", 18), ("std-22", 22)];
+
+pub struct FontCatalog {
+    faces: HashMap<String, &'static LvglFont>,
+    theme: Option<String>,
+    widgets: Vec<(String, String)>,
+}
+
+impl FontCatalog {
+    pub fn new(config: FontConfig) -> Result<&'static Self, AfbError> {
+        let mut faces = HashMap::new();
+        for (uid, size) in BUILTIN_FACES {
+            faces.insert(uid.to_string(), LvglFontFile::builtin(size));
+        }
+
+        // a missing file should not leave the screen blank, use closest builtin size instead
+        let mut loaded = Vec::new();
+        for face in &config.faces {
+            if faces.contains_key(&face.uid) {
+                return afb_error!("font-config-fail", "duplicated font uid:{}", face.uid);
+            }
+            let path = if face.file.starts_with('/') || config.dir.is_empty() {
+                face.file.clone()
+            } else {
+                format!("{}/{}", config.dir, face.file)
+            };
+            match LvglFontFile::load(path.as_str(), face.size) {
+                Some(font) => {
+                    faces.insert(face.uid.clone(), font);
+                    loaded.push(face);
+                }
+                None => {
+                    afb_log_msg!(Warning, None, "fail to load font:{} using builtin", path);
+                    faces.insert(face.uid.clone(), LvglFontFile::builtin(face.size));
+                }
+            }
+        }
+
+        // chain loaded fonts, a loop would hang lvgl glyph lookup
+        for face in &loaded {
+            let mut next = face.fallback.clone();
+            let mut depth = 0;
+            while let Some(uid) = next {
+                depth += 1;
+                if depth > config.faces.len() {
+                    return afb_error!("font-fallback-loop", "font:{} fallback chain loops", face.uid);
+                }
+                next = match config.faces.iter().find(|value| value.uid == uid) {
+                    Some(value) => value.fallback.clone(),
+                    None if faces.contains_key(&uid) => None,
+                    None => return afb_error!("font-fallback-unknown", "font:{} unknown fallback:{}", face.uid, uid),
+                };
+            }
+            let fallback = match &face.fallback {
+                Some(uid) => faces[uid],
+                None => LvglFontFile::builtin(face.size),
+            };
+            LvglFontFile::set_fallback(faces[&face.uid], fallback);
+        }
+
+        if let Some(uid) = &config.theme {
+            if !faces.contains_key(uid) {
+                return afb_error!("font-config-fail", "unknown theme font:{}", uid);
+            }
+        }
+        let mut widgets = Vec::new();
+        for (widget, uid) in config.widgets {
+            if !faces.contains_key(&uid) {
+                return afb_error!("font-config-fail", "widget:{} unknown font:{}", widget, uid);
+            }
+            widgets.push((widget, uid));
+        }
+
+        let catalog = FontCatalog {
+            faces,
+            theme: config.theme,
+            widgets,
+        };
+        Ok(Box::leak(Box::new(catalog)))
+    }
+
+    pub fn get_font(&self, uid: &str) -> Result<&'static LvglFont, AfbError> {
+        match self.faces.get(uid) {
+            Some(font) => Ok(*font),
+            None => afb_error!("font-unknown", "unknown font:{}", uid),
+        }
+    }
+
+    // theme font defaults to montserrat 14
+    pub fn get_theme(&self) -> &'static LvglFont {
+        match &self.theme {
+            Some(uid) => self.faces[uid],
+            None => LvglMkFont::std_14(),
+        }
+    }
+
+    // configured override for one widget, None when it keeps its creation font
+    pub fn get_widget_font(&self, uid: &str) -> Option<&'static LvglFont> {
+        self.widgets
+            .iter()
+            .find(|(widget, _)| widget == uid)
+            .map(|(_, face)| self.faces[face])
+    }
+
+    // per widget override, called once panel is drawn
+    pub fn apply(&self, display: &DisplayHandle) -> Result<(), AfbError> {
+        let mut updates: Vec<(&'static LvglWidget, &'static LvglFont)> = Vec::new();
+        for (uid, face) in &self.widgets {
+            let widget = match display.get_panel().iter().find(|widget| widget.get_uid() == uid.as_str()) {
+                Some(widget) => *widget,
+                None => return afb_error!("font-widget-unknown", "no widget uid:{}", uid),
+            };
+            updates.push((widget, self.get_font(face)?));
+        }
+        display.get_dispatch().post(move || {
+            for (widget, font) in &updates {
+                widget.set_font(*font);
+            }
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,227 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ */
+
+use crate::prelude::*;
+use afbv4::prelude::*;
+use display_lvgl_gui::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{Mutex, MutexGuard};
+use typesv4::prelude::*;
+
+// static label uid -> catalog key, english text is used when key is missing
+const LOCALE_LABELS: [(&str, &str, &str); 17] = [
+    ("ChargeVoltsTxt", "voltage", "Voltage"),
+    ("ChargeImpsTxt", "current", "Current"),
+    ("ChargeEnergyTxt", "energy", "Energy"),
+    ("Label Switch", "smart-charging", "Smart Charging"),
+    ("GridTxt", "grid", "Grid"),
+    ("SummaryTitle", "summary-title", "Charging completed"),
+    ("SummaryEnergyTxt", "energy", "Energy"),
+    ("SummaryDurationTxt", "duration", "Duration"),
+    ("SummaryAvgPowerTxt", "avg-power", "Average power"),
+    ("SummaryPeakPowerTxt", "peak-power", "Peak power"),
+    ("SummaryCostTxt", "cost", "Cost"),
+    ("SummaryTagTxt", "badge", "Badge"),
+    ("FaultCodeTxt", "fault-code", "Code"),
+    ("FaultMessageTxt", "fault-problem", "Problem"),
+    ("FaultActionTxt", "fault-action", "Action"),
+    ("OverCurrentMsg", "overcurrent-msg", "Grid over-current: charging power is reduced"),
+    ("SaverMsg", "saver-msg", "Touch screen to start"),
+];
+
+fn default_languages() -> Vec<String> {
+    vec!["en".to_string()]
+}
+
+// binding config 'i18n' object, catalogs are read from '{dir}/{language}.json'
+// builtin montserrat is ascii only, accented catalogs need a 'fonts' face on translated labels
+#[derive(Deserialize, Debug, Clone)]
+pub struct LocaleConfig {
+    #[serde(default)]
+    pub dir: String,
+    #[serde(default = "default_languages")]
+    pub languages: Vec<String>,
+    #[serde(default)]
+    pub default: String,
+}
+
+impl Default for LocaleConfig {
+    fn default() -> Self {
+        LocaleConfig {
+            dir: String::new(),
+            languages: default_languages(),
+            default: String::new(),
+        }
+    }
+}
+
+impl LocaleConfig {
+    pub fn from_jsonc(ji18n: JsoncObj) -> Result<Self, AfbError> {
+        match serde_json::from_str::<LocaleConfig>(ji18n.to_string().as_str()) {
+            Ok(value) => Ok(value),
+            Err(error) => afb_error!("i18n-config-fail", "invalid i18n config:{}", error),
+        }
+    }
+}
+
+// english is built in, its catalog file is optional
+fn load_catalog(dir: &str, language: &str) -> Result<HashMap<String, String>, AfbError> {
+    let path = format!("{}/{}.json", dir, language);
+    let data = match fs::read_to_string(path.as_str()) {
+        Ok(value) => value,
+        Err(_) if language == "en" => return Ok(HashMap::new()),
+        Err(error) => return afb_error!("i18n-catalog-read", "fail to read catalog:{} error:{}", path, error),
+    };
+    match serde_json::from_str::<HashMap<String, String>>(data.as_str()) {
+        Ok(value) => Ok(value),
+        Err(error) => afb_error!("i18n-catalog-parse", "invalid catalog:{} error:{}", path, error),
+    }
+}
+
+// panels holding translated dynamic texts redraw them on language change
+pub trait LocaleListener: Sync {
+    fn locale_changed(&'static self);
+}
+
+struct LocaleData {
+    current: usize,
+    labels: Vec<(&'static LvglLabel, &'static str, &'static str)>,
+    listeners: Vec<&'static dyn LocaleListener>,
+}
+
+pub struct LocaleHandle {
+    languages: Vec<String>,
+    catalogs: Vec<HashMap<String, String>>,
+    data_set: Mutex<LocaleData>,
+    dispatch: &'static LvglDispatch,
+}
+
+impl LocaleHandle {
+    pub fn new(config: LocaleConfig, dispatch: &'static LvglDispatch) -> Result<&'static Self, AfbError> {
+        if config.languages.is_empty() {
+            return afb_error!("i18n-config-fail", "i18n languages should not be empty");
+        }
+        let mut catalogs = Vec::new();
+        for language in &config.languages {
+            catalogs.push(load_catalog(config.dir.as_str(), language.as_str())?);
+        }
+        let current = config
+            .languages
+            .iter()
+            .position(|language| *language == config.default)
+            .unwrap_or(0);
+
+        let handle = LocaleHandle {
+            languages: config.languages,
+            catalogs,
+            data_set: Mutex::new(LocaleData {
+                current,
+                labels: Vec::new(),
+                listeners: Vec::new(),
+            }),
+            dispatch,
+        };
+        Ok(Box::leak(Box::new(handle)))
+    }
+
+    fn get_state(&self) -> Result<MutexGuard<'_, LocaleData>, AfbError> {
+        match self.data_set.lock() {
+            Ok(guard) => Ok(guard),
+            Err(_) => afb_error!("i18n-state-lock", "fail to lock locale state"),
+        }
+    }
+
+    // attach translated labels once panel is drawn
+    pub fn bind(&'static self, display: &DisplayHandle) -> Result<(), AfbError> {
+        {
+            let mut state = self.get_state()?;
+            for (uid, key, english) in LOCALE_LABELS {
+                state.labels.push((get_widget::<LvglLabel>(display, uid)?, key, english));
+            }
+        }
+        self.apply()
+    }
+
+    // None when current catalog does not define the key
+    pub fn get_text(&self, key: &str) -> Option<String> {
+        let current = match self.data_set.lock() {
+            Ok(state) => state.current,
+            Err(_) => return None,
+        };
+        self.catalogs[current].get(key).cloned()
+    }
+
+    // catalog text or english default
+    pub fn translate(&self, key: &str, english: &str) -> String {
+        match self.get_text(key) {
+            Some(text) => text,
+            None => english.to_string(),
+        }
+    }
+
+    pub fn add_listener(&self, listener: &'static dyn LocaleListener) -> Result<(), AfbError> {
+        self.get_state()?.listeners.push(listener);
+        Ok(())
+    }
+
+    pub fn get_locale(&self) -> Result<&str, AfbError> {
+        let current = self.get_state()?.current;
+        Ok(self.languages[current].as_str())
+    }
+
+    pub fn set_locale(&'static self, language: &str) -> Result<(), AfbError> {
+        match self.languages.iter().position(|value| value == language) {
+            Some(index) => self.get_state()?.current = index,
+            None => return afb_error!("i18n-locale-unknown", "no catalog for language:{}", language),
+        }
+        self.apply()
+    }
+
+    // language icon tap
+    pub fn cycle(&'static self) -> Result<(), AfbError> {
+        {
+            let mut state = self.get_state()?;
+            state.current = (state.current + 1) % self.languages.len();
+        }
+        self.apply()
+    }
+
+    fn apply(&'static self) -> Result<(), AfbError> {
+        let state = self.get_state()?;
+        let catalog = &self.catalogs[state.current];
+        let texts: Vec<(&'static LvglLabel, String)> = state
+            .labels
+            .iter()
+            .map(|&(label, key, english)| {
+                let text = match catalog.get(key) {
+                    Some(text) => text.clone(),
+                    None => english.to_string(),
+                };
+                (label, text)
+            })
+            .collect();
+        let listeners = state.listeners.clone();
+        drop(state);
+
+        self.dispatch.post(move || {
+            for (label, text) in &texts {
+                label.set_value(text.as_str());
+            }
+        });
+        for listener in listeners {
+            listener.locale_changed();
+        }
+        Ok(())
+    }
+}
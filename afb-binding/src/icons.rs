@@ -0,0 +1,277 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::prelude::*;
+use afbv4::prelude::*;
+use display_lvgl_gui::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use typesv4::prelude::*;
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct IconTint {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+// one state entry, 'asset' is any AssetPixmap name (compiled or runtime)
+#[derive(Deserialize, Debug, Clone)]
+pub struct IconEntry {
+    pub asset: String,
+    #[serde(default)]
+    pub tint: Option<IconTint>,
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+// binding config 'icons' object, per table state name -> entry, missing states keep defaults
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct IconConfig {
+    #[serde(default)]
+    pub auth: HashMap<String, IconEntry>,
+    #[serde(default)]
+    pub plug: HashMap<String, IconEntry>,
+    #[serde(default)]
+    pub power: HashMap<String, IconEntry>,
+}
+
+impl IconConfig {
+    pub fn from_jsonc(jicons: JsoncObj) -> Result<Self, AfbError> {
+        match serde_json::from_str::<IconConfig>(jicons.to_string().as_str()) {
+            Ok(value) => Ok(value),
+            Err(error) => afb_error!("icons-config-fail", "invalid icons config:{}", error),
+        }
+    }
+}
+
+// colour-blind safe tints (Okabe-Ito palette) used in accessibility mode
+const SAFE_OK: IconTint = IconTint { red: 0, green: 158, blue: 115 };
+const SAFE_FAIL: IconTint = IconTint { red: 213, green: 94, blue: 0 };
+const SAFE_WAIT: IconTint = IconTint { red: 230, green: 159, blue: 0 };
+const SAFE_IDLE: IconTint = IconTint { red: 86, green: 180, blue: 233 };
+const SAFE_ACTIVE: IconTint = IconTint { red: 0, green: 114, blue: 178 };
+
+// state name, default asset, default text, accessibility tint
+const AUTH_ICONS: [(&str, &str, &str, IconTint); 4] = [
+    ("done", "nfc_done", "Badge accepted", SAFE_OK),
+    ("fail", "nfc_fail", "Badge refused", SAFE_FAIL),
+    ("pending", "nfc_pending", "Checking badge", SAFE_WAIT),
+    ("idle", "nfc_idle", "Present badge", SAFE_IDLE),
+];
+
+const PLUG_ICONS: [(&str, &str, &str, IconTint); 5] = [
+    ("plugin", "plug_connected_unlocked", "Plugged", SAFE_ACTIVE),
+    ("lock", "plug_connected_locked", "Locked", SAFE_OK),
+    ("error", "plug_error", "Plug error", SAFE_FAIL),
+    ("plugout", "plug_disconnected", "Unplugged", SAFE_IDLE),
+    ("unknown", "plug_unknow", "Plug unknown", SAFE_WAIT),
+];
+
+// 'pending' is shown while plug is locked and auth not yet done, 'fault' on permanent faults,
+// 'reserved' while an accepted reservation holds the station
+const POWER_ICONS: [(&str, &str, &str, IconTint); 7] = [
+    ("start", "station_reserved", "Starting", SAFE_WAIT),
+    ("charging", "station_charging", "Charging", SAFE_ACTIVE),
+    ("stop", "station_completed", "Completed", SAFE_OK),
+    ("idle", "station_available", "Available", SAFE_IDLE),
+    ("pending", "station_pending_autho", "Waiting authorization", SAFE_WAIT),
+    ("fault", "station_out_of_order", "Out of order", SAFE_FAIL),
+    ("reserved", "station_reserved", "Reserved", SAFE_WAIT),
+];
+
+// text is looked up in locale catalog as 'icon-{table}-{state}', then config text, then english default
+// configured text is always shown, default one only in accessibility mode
+pub struct StateIcon {
+    pub pixmap: &'static LvglImgDsc,
+    pub tint: Option<IconTint>,
+    pub safe_tint: IconTint,
+    pub key: String,
+    pub text: Option<String>,
+    pub default: &'static str,
+}
+
+fn build_table(
+    table: &str,
+    defaults: &[(&'static str, &'static str, &'static str, IconTint)],
+    mut config: HashMap<String, IconEntry>,
+) -> Result<Vec<(&'static str, StateIcon)>, AfbError> {
+    let mut icons = Vec::new();
+    for (state, asset, text, safe_tint) in defaults {
+        let entry = match config.remove(*state) {
+            Some(entry) => entry,
+            None => IconEntry {
+                asset: asset.to_string(),
+                tint: None,
+                text: None,
+            },
+        };
+        let pixmap = match AssetPixmap::by_name(entry.asset.as_str()) {
+            Some(pixmap) => pixmap,
+            None => return afb_error!("icons-config-fail", "{}:{} unknown asset:{}", table, state, entry.asset),
+        };
+        let icon = StateIcon {
+            pixmap,
+            tint: entry.tint,
+            safe_tint: *safe_tint,
+            key: format!("icon-{}-{}", table, state),
+            text: entry.text,
+            default: *text,
+        };
+        icons.push((*state, icon));
+    }
+    if let Some(state) = config.keys().next() {
+        return afb_error!("icons-config-fail", "{} unknown state:{}", table, state);
+    }
+    Ok(icons)
+}
+
+fn auth_key(state: &AuthMsg) -> &'static str {
+    match state {
+        AuthMsg::Done => "done",
+        AuthMsg::Fail => "fail",
+        AuthMsg::Pending => "pending",
+        AuthMsg::Idle => "idle",
+    }
+}
+
+fn plug_key(state: &PlugState) -> &'static str {
+    match state {
+        PlugState::PlugIn => "plugin",
+        PlugState::Lock => "lock",
+        PlugState::Error => "error",
+        PlugState::PlugOut => "plugout",
+        PlugState::Unknown => "unknown",
+    }
+}
+
+// one status pixmap, its text label and last displayed state
+struct IconSlot {
+    widget: &'static LvglPixmap,
+    label: &'static LvglLabel,
+    icons: Vec<(&'static str, StateIcon)>,
+    current: Mutex<Option<&'static str>>,
+}
+
+impl IconSlot {
+    fn new(
+        display: &DisplayHandle,
+        widget: &str,
+        label: &str,
+        icons: Vec<(&'static str, StateIcon)>,
+    ) -> Result<Self, AfbError> {
+        let slot = IconSlot {
+            widget: get_widget::<LvglPixmap>(display, widget)?,
+            label: get_widget::<LvglLabel>(display, label)?,
+            icons,
+            current: Mutex::new(None),
+        };
+        Ok(slot)
+    }
+}
+
+// status pixmaps shared by event and async read paths
+pub struct StatusIcons {
+    auth: IconSlot,
+    plug: IconSlot,
+    power: IconSlot,
+    access: AtomicBool,
+    locale: &'static LocaleHandle,
+    dispatch: &'static LvglDispatch,
+}
+
+impl StatusIcons {
+    pub fn new(
+        display: &DisplayHandle,
+        config: IconConfig,
+        locale: &'static LocaleHandle,
+    ) -> Result<&'static Self, AfbError> {
+        let icons = StatusIcons {
+            auth: IconSlot::new(display, "Pixmap-auth-status", "Text-auth-status", build_table("auth", &AUTH_ICONS, config.auth)?)?,
+            plug: IconSlot::new(display, "Pixmap-connect-status", "Text-connect-status", build_table("plug", &PLUG_ICONS, config.plug)?)?,
+            power: IconSlot::new(display, "Pixmap-charge-status", "Text-charge-status", build_table("power", &POWER_ICONS, config.power)?)?,
+            access: AtomicBool::new(false),
+            locale,
+            dispatch: display.get_dispatch(),
+        };
+        let icons: &'static StatusIcons = Box::leak(Box::new(icons));
+        locale.add_listener(icons)?;
+        Ok(icons)
+    }
+
+    // accessibility mode adds default state text and replaces tints with colour-blind safe ones
+    fn post(&'static self, slot: &'static IconSlot, state: &str) {
+        let (key, icon) = match slot.icons.iter().find(|(key, _)| *key == state) {
+            Some((key, icon)) => (*key, icon),
+            None => return,
+        };
+        if let Ok(mut current) = slot.current.lock() {
+            *current = Some(key);
+        }
+        let access = self.access.load(Ordering::Relaxed);
+        let text = match &icon.text {
+            Some(text) => self.locale.translate(icon.key.as_str(), text.as_str()),
+            None if access => self.locale.translate(icon.key.as_str(), icon.default),
+            None => String::new(),
+        };
+        self.dispatch.post(move || {
+            let tint = if access { Some(icon.safe_tint) } else { icon.tint };
+            slot.widget.set_value(icon.pixmap);
+            slot.widget.set_tint(tint.map(|tint| (tint.red, tint.green, tint.blue)));
+            slot.label.set_value(text.as_str());
+        });
+    }
+
+    pub fn set_auth(&'static self, state: &AuthMsg) {
+        self.post(&self.auth, auth_key(state));
+    }
+
+    pub fn set_plug(&'static self, state: &PlugState) {
+        self.post(&self.plug, plug_key(state));
+    }
+
+    // power states without a chmgr enum ('pending', 'fault', 'reserved') are addressed by name
+    pub fn set_power(&'static self, state: &str) {
+        self.post(&self.power, state);
+    }
+
+    // redraw current states with or without accessibility text and tints
+    pub fn set_access(&'static self, access: bool) {
+        self.access.store(access, Ordering::Relaxed);
+        self.redraw();
+    }
+
+    fn redraw(&'static self) {
+        for slot in [&self.auth, &self.plug, &self.power] {
+            let current = match slot.current.lock() {
+                Ok(value) => *value,
+                Err(_) => None,
+            };
+            if let Some(state) = current {
+                self.post(slot, state);
+            }
+        }
+    }
+}
+
+impl LocaleListener for StatusIcons {
+    fn locale_changed(&'static self) {
+        self.redraw();
+    }
+}
@@ -24,7 +24,63 @@ mod verbs;
 #[path = "binding.rs"]
 mod binding;
 
+#[path = "session.rs"]
+mod session;
+
+#[path = "tariff.rs"]
+mod tariff;
+
+#[path = "fault.rs"]
+mod fault;
+
+#[path = "screens.rs"]
+mod screens;
+
+#[path = "watchdog.rs"]
+mod watchdog;
+
+#[path = "qrcode.rs"]
+mod qrcode;
+
+#[path = "network.rs"]
+mod network;
+
+#[path = "i18n.rs"]
+mod i18n;
+
+#[path = "fonts.rs"]
+mod fonts;
+
+#[path = "icons.rs"]
+mod icons;
+
+#[path = "themes.rs"]
+mod themes;
+
+#[path = "access.rs"]
+mod access;
+
+#[path = "backlight.rs"]
+mod backlight;
+
+#[path = "carousel.rs"]
+mod carousel;
+
 pub(crate) mod prelude {
     pub(crate) use crate::verbs::*;
     pub(crate) use crate::binding::*;
+    pub(crate) use crate::session::*;
+    pub(crate) use crate::tariff::*;
+    pub(crate) use crate::fault::*;
+    pub(crate) use crate::screens::*;
+    pub(crate) use crate::watchdog::*;
+    pub(crate) use crate::qrcode::*;
+    pub(crate) use crate::network::*;
+    pub(crate) use crate::i18n::*;
+    pub(crate) use crate::fonts::*;
+    pub(crate) use crate::icons::*;
+    pub(crate) use crate::themes::*;
+    pub(crate) use crate::access::*;
+    pub(crate) use crate::backlight::*;
+    pub(crate) use crate::carousel::*;
 }
\ No newline at end of file
@@ -0,0 +1,98 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ */
+
+use afbv4::prelude::*;
+use serde::Deserialize;
+use std::fs;
+
+fn default_ethernet() -> String {
+    "eth0".to_string()
+}
+
+fn default_wifi() -> String {
+    "wlan0".to_string()
+}
+
+fn default_period() -> u32 {
+    5
+}
+
+// binding config 'network' object, interfaces are polled from sysfs/procfs
+#[derive(Deserialize, Debug, Clone)]
+pub struct NetworkConfig {
+    #[serde(default = "default_ethernet")]
+    pub ethernet: String,
+    #[serde(default = "default_wifi")]
+    pub wifi: String,
+    // polling period in seconds
+    #[serde(default = "default_period")]
+    pub period: u32,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        NetworkConfig {
+            ethernet: default_ethernet(),
+            wifi: default_wifi(),
+            period: default_period(),
+        }
+    }
+}
+
+impl NetworkConfig {
+    pub fn from_jsonc(jnetwork: JsoncObj) -> Result<Self, AfbError> {
+        match serde_json::from_str::<NetworkConfig>(jnetwork.to_string().as_str()) {
+            Ok(value) => Ok(value),
+            Err(error) => afb_error!("network-config-fail", "invalid network config:{}", error),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum WifiLevel {
+    None,
+    Weak,
+    Good,
+}
+
+// missing interface is reported as down
+pub fn link_is_up(iface: &str) -> bool {
+    match fs::read_to_string(format!("/sys/class/net/{}/operstate", iface)) {
+        Ok(state) => state.trim() == "up",
+        Err(_) => false,
+    }
+}
+
+// /proc/net/wireless link quality is given out of 70
+pub fn wifi_level(iface: &str) -> WifiLevel {
+    let wireless = match fs::read_to_string("/proc/net/wireless") {
+        Ok(value) => value,
+        Err(_) => return WifiLevel::None,
+    };
+    let prefix = format!("{}:", iface);
+    for line in wireless.lines() {
+        let line = line.trim_start();
+        if !line.starts_with(prefix.as_str()) {
+            continue;
+        }
+        let quality = line
+            .split_whitespace()
+            .nth(2)
+            .and_then(|value| value.trim_end_matches('.').parse::<u32>().ok());
+        return match quality {
+            Some(value) if value * 100 / 70 >= 50 => WifiLevel::Good,
+            Some(value) if value > 0 => WifiLevel::Weak,
+            _ => WifiLevel::None,
+        };
+    }
+    WifiLevel::None
+}
@@ -0,0 +1,64 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ */
+
+use afbv4::prelude::*;
+use serde::Deserialize;
+
+fn default_station() -> String {
+    "tux-evse".to_string()
+}
+
+fn default_connector() -> u32 {
+    1
+}
+
+fn default_payload() -> String {
+    "EVSE;station={station};connector={connector};session={session}".to_string()
+}
+
+// binding config 'qrcode' object, payload is a template filled from live state
+#[derive(Deserialize, Debug, Clone)]
+pub struct QrcodeConfig {
+    #[serde(default = "default_station")]
+    pub station: String,
+    #[serde(default = "default_connector")]
+    pub connector: u32,
+    #[serde(default = "default_payload")]
+    pub payload: String,
+}
+
+impl Default for QrcodeConfig {
+    fn default() -> Self {
+        QrcodeConfig {
+            station: default_station(),
+            connector: default_connector(),
+            payload: default_payload(),
+        }
+    }
+}
+
+impl QrcodeConfig {
+    pub fn from_jsonc(jqrcode: JsoncObj) -> Result<Self, AfbError> {
+        match serde_json::from_str::<QrcodeConfig>(jqrcode.to_string().as_str()) {
+            Ok(value) => Ok(value),
+            Err(error) => afb_error!("qrcode-config-fail", "invalid qrcode config:{}", error),
+        }
+    }
+
+    // supported tags: {station} {connector} {session}, session (OCPP transaction id) is empty when idle or unknown
+    pub fn fill(&self, template: &str, session: &str) -> String {
+        template
+            .replace("{station}", self.station.as_str())
+            .replace("{connector}", self.connector.to_string().as_str())
+            .replace("{session}", session)
+    }
+}
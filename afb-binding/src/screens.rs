@@ -0,0 +1,883 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ */
+
+use crate::prelude::*;
+use afbv4::prelude::*;
+use display_lvgl_gui::prelude::*;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use typesv4::prelude::*;
+
+// end of session summary overlay
+pub struct SummaryScreen {
+    overlay: &'static LvglOverlay,
+    energy: &'static LvglLabel,
+    duration: &'static LvglLabel,
+    avg_power: &'static LvglLabel,
+    peak_power: &'static LvglLabel,
+    cost: &'static LvglLabel,
+    tagid: &'static LvglLabel,
+    qrcode: &'static LvglQrcode,
+    dispatch: &'static LvglDispatch,
+}
+
+impl SummaryScreen {
+    pub fn new(display: &DisplayHandle) -> Result<&'static Self, AfbError> {
+        let summary = SummaryScreen {
+            overlay: get_widget::<LvglOverlay>(display, "Overlay-summary")?,
+            energy: get_widget::<LvglLabel>(display, "SummaryEnergyVal")?,
+            duration: get_widget::<LvglLabel>(display, "SummaryDurationVal")?,
+            avg_power: get_widget::<LvglLabel>(display, "SummaryAvgPowerVal")?,
+            peak_power: get_widget::<LvglLabel>(display, "SummaryPeakPowerVal")?,
+            cost: get_widget::<LvglLabel>(display, "SummaryCostVal")?,
+            tagid: get_widget::<LvglLabel>(display, "SummaryTagVal")?,
+            qrcode: get_widget::<LvglQrcode>(display, "SummaryQrcode")?,
+            dispatch: display.get_dispatch(),
+        };
+        Ok(Box::leak(Box::new(summary)))
+    }
+
+    pub fn show(&'static self, session: &SessionHandle) -> Result<(), AfbError> {
+        let summary = session.get_summary()?;
+        let currency = session.get_tariff().map(|tariff| tariff.currency.as_str());
+
+        let energy = format!("{:.2} kW.h", summary.energy as f64 / 1000.0);
+        let duration = fmt_duration(summary.duration);
+        let avg_power = format!("{:.2} kW", summary.avg_power as f64 / 1000.0);
+        let peak_power = format!("{:.2} kW", summary.peak_power as f64 / 1000.0);
+        let cost = match (session.get_tariff(), summary.cost) {
+            (Some(tariff), Some(cost)) => tariff.fmt_cost(cost),
+            _ => "--".to_string(),
+        };
+        let receipt = summary.get_receipt(currency);
+        let tagid = summary.tagid;
+
+        self.dispatch.post(move || {
+            self.energy.set_value(energy.as_str());
+            self.duration.set_value(duration.as_str());
+            self.avg_power.set_value(avg_power.as_str());
+            self.peak_power.set_value(peak_power.as_str());
+            self.cost.set_value(cost.as_str());
+            self.tagid.set_value(tagid.as_str());
+            self.qrcode.set_value(receipt.as_str());
+            self.overlay.set_hidden(false);
+        });
+        Ok(())
+    }
+
+    pub fn hide(&'static self) {
+        self.dispatch.post(move || {
+            self.overlay.set_hidden(true);
+        });
+    }
+}
+
+// fault overlay, recoverable faults are cleared on plug-out, permanent one stays until chmgr is back to idle
+pub struct FaultScreen {
+    overlay: &'static LvglOverlay,
+    title: &'static LvglLabel,
+    code: &'static LvglLabel,
+    message: &'static LvglLabel,
+    action: &'static LvglLabel,
+    qrcode: &'static LvglQrcode,
+    config: FaultConfig,
+    locale: &'static LocaleHandle,
+    latch: FaultLatch,
+    shown: Mutex<Option<FaultInfo>>,
+    dispatch: &'static LvglDispatch,
+}
+
+impl FaultScreen {
+    pub fn new(
+        display: &DisplayHandle,
+        config: FaultConfig,
+        locale: &'static LocaleHandle,
+    ) -> Result<&'static Self, AfbError> {
+        let fault = FaultScreen {
+            overlay: get_widget::<LvglOverlay>(display, "Overlay-fault")?,
+            title: get_widget::<LvglLabel>(display, "FaultTitle")?,
+            code: get_widget::<LvglLabel>(display, "FaultCodeVal")?,
+            message: get_widget::<LvglLabel>(display, "FaultMessageVal")?,
+            action: get_widget::<LvglLabel>(display, "FaultActionVal")?,
+            qrcode: get_widget::<LvglQrcode>(display, "FaultQrcode")?,
+            config,
+            locale,
+            latch: FaultLatch::new(),
+            shown: Mutex::new(None),
+            dispatch: display.get_dispatch(),
+        };
+        let fault: &'static FaultScreen = Box::leak(Box::new(fault));
+        locale.add_listener(fault)?;
+        Ok(fault)
+    }
+
+    pub fn show(&'static self, info: &FaultInfo) {
+        if !self.latch.raise(info) {
+            return;
+        }
+        if let Ok(mut shown) = self.shown.lock() {
+            *shown = Some(*info);
+        }
+        self.draw(info);
+    }
+
+    fn draw(&'static self, info: &FaultInfo) {
+        let title = if info.permanent {
+            self.locale.translate("fault-title-permanent", "Out of order")
+        } else {
+            self.locale.translate("fault-title", "Charging interrupted")
+        };
+        let code = info.code;
+        let message = self.get_text(info.message);
+        let action = self.get_text(info.action);
+        let support = self.config.get_support(info.code);
+
+        self.dispatch.post(move || {
+            self.title.set_value(title.as_str());
+            self.code.set_value(code);
+            self.message.set_value(message.as_str());
+            self.action.set_value(action.as_str());
+            self.qrcode.set_value(support.as_str());
+            self.overlay.set_hidden(false);
+        });
+    }
+
+    // current language catalog first, then config override or english default
+    fn get_text(&self, key: &str) -> String {
+        match self.locale.get_text(key) {
+            Some(text) => text,
+            None => self.config.get_text(key),
+        }
+    }
+
+    pub fn is_permanent(&self) -> bool {
+        self.latch.is_permanent()
+    }
+
+    // only recoverable faults are cleared
+    pub fn clear(&'static self) {
+        if !self.is_permanent() {
+            self.hide();
+        }
+    }
+
+    // chmgr reports a non fault state, permanent fault is over
+    pub fn reset(&'static self) {
+        self.latch.release();
+        self.hide();
+    }
+
+    fn hide(&'static self) {
+        if let Ok(mut shown) = self.shown.lock() {
+            *shown = None;
+        }
+        self.dispatch.post(move || {
+            self.overlay.set_hidden(true);
+        });
+    }
+}
+
+impl LocaleListener for FaultScreen {
+    fn locale_changed(&'static self) {
+        let shown = match self.shown.lock() {
+            Ok(value) => *value,
+            Err(_) => None,
+        };
+        if let Some(info) = shown {
+            self.draw(&info);
+        }
+    }
+}
+
+// refused/cancelled/expired feedback display time
+const RESERVATION_FEEDBACK: Duration = Duration::from_secs(5);
+
+struct ReservationData {
+    active: bool,
+    stop: Duration,
+    feedback_until: Option<Duration>,
+    // catalog key and english text, kept to redraw status on language change
+    status: Option<(&'static str, &'static str)>,
+}
+
+// reservation status, masked badge and countdown until reservation expires
+pub struct ReservationPanel {
+    status: &'static LvglLabel,
+    tagid: &'static LvglLabel,
+    countdown: &'static LvglLabel,
+    icons: &'static StatusIcons,
+    locale: &'static LocaleHandle,
+    data_set: Mutex<ReservationData>,
+    dispatch: &'static LvglDispatch,
+}
+
+impl ReservationPanel {
+    pub fn new(
+        display: &DisplayHandle,
+        icons: &'static StatusIcons,
+        locale: &'static LocaleHandle,
+    ) -> Result<&'static Self, AfbError> {
+        let panel = ReservationPanel {
+            status: get_widget::<LvglLabel>(display, "ReservationStatus")?,
+            tagid: get_widget::<LvglLabel>(display, "ReservationTag")?,
+            countdown: get_widget::<LvglLabel>(display, "ReservationCountdown")?,
+            icons,
+            locale,
+            data_set: Mutex::new(ReservationData {
+                active: false,
+                stop: Duration::new(0, 0),
+                feedback_until: None,
+                status: None,
+            }),
+            dispatch: display.get_dispatch(),
+        };
+        let panel: &'static ReservationPanel = Box::leak(Box::new(panel));
+        locale.add_listener(panel)?;
+        Ok(panel)
+    }
+
+    fn get_state(&self) -> Result<MutexGuard<'_, ReservationData>, AfbError> {
+        match self.data_set.lock() {
+            Ok(guard) => Ok(guard),
+            Err(_) => afb_error!("reservation-state-lock", "fail to lock reservation state"),
+        }
+    }
+
+    fn set_label(&'static self, label: &'static LvglLabel, text: &str) {
+        let text = text.to_string();
+        self.dispatch.post(move || {
+            label.set_value(text.as_str());
+        });
+    }
+
+    fn show_status(&'static self, state: &mut ReservationData, status: Option<(&'static str, &'static str)>) {
+        state.status = status;
+        let text = match status {
+            Some((key, english)) => self.locale.translate(key, english),
+            None => String::new(),
+        };
+        self.set_label(self.status, text.as_str());
+    }
+
+    // transient message, panel is cleared once feedback delay is over
+    fn set_feedback(&'static self, state: &mut ReservationData, status: (&'static str, &'static str)) {
+        state.active = false;
+        state.feedback_until = Some(time_now() + RESERVATION_FEEDBACK);
+        self.show_status(state, Some(status));
+        self.set_label(self.countdown, "");
+    }
+
+    pub fn set_status(&'static self, status: &ReservationStatus) -> Result<(), AfbError> {
+        let mut state = self.get_state()?;
+        match status {
+            ReservationStatus::Accepted => {
+                state.active = true;
+                state.feedback_until = None;
+                self.show_status(&mut state, Some(("reservation-accepted", "Reserved")));
+                self.icons.set_power("reserved");
+            }
+            ReservationStatus::Pending | ReservationStatus::Request => {
+                self.show_status(&mut state, Some(("reservation-pending", "Reservation pending")));
+            }
+            ReservationStatus::Refused => {
+                self.set_feedback(&mut state, ("reservation-refused", "Reservation refused"));
+            }
+            ReservationStatus::Cancel => {
+                if state.active {
+                    self.icons.set_power("idle");
+                }
+                self.set_feedback(&mut state, ("reservation-cancelled", "Reservation cancelled"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn set_session(&'static self, session: &ReservationSession) -> Result<(), AfbError> {
+        {
+            let mut state = self.get_state()?;
+            state.stop = session.stop;
+        }
+        self.set_label(self.tagid, mask_tagid(session.tagid.as_str()).as_str());
+        self.set_status(&session.status)?;
+        self.tick()
+    }
+
+    // reservation consumed by a new charging session
+    pub fn clear(&'static self) -> Result<(), AfbError> {
+        let mut state = self.get_state()?;
+        state.active = false;
+        state.feedback_until = None;
+        self.show_status(&mut state, None);
+        self.set_label(self.tagid, "");
+        self.set_label(self.countdown, "");
+        Ok(())
+    }
+
+    // called every second from reservation timer
+    pub fn tick(&'static self) -> Result<(), AfbError> {
+        let mut state = self.get_state()?;
+        let now = time_now();
+
+        if let Some(deadline) = state.feedback_until {
+            if now >= deadline {
+                state.feedback_until = None;
+                self.show_status(&mut state, None);
+                self.set_label(self.tagid, "");
+            }
+            return Ok(());
+        }
+
+        if state.active && !state.stop.is_zero() {
+            if now >= state.stop {
+                self.icons.set_power("idle");
+                self.set_feedback(&mut state, ("reservation-expired", "Reservation expired"));
+            } else {
+                self.set_label(self.countdown, fmt_duration(state.stop - now).as_str());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl LocaleListener for ReservationPanel {
+    fn locale_changed(&'static self) {
+        if let Ok(mut state) = self.get_state() {
+            let status = state.status;
+            self.show_status(&mut state, status);
+        }
+    }
+}
+
+// charging profiles are attached to an OCPP transaction, other limits come from grid load management
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum LimitReason {
+    Ocpp,
+    Grid,
+}
+
+impl LimitReason {
+    fn from_limit(limit: &PowerLimit) -> Self {
+        if limit.tid > 0 {
+            LimitReason::Ocpp
+        } else {
+            LimitReason::Grid
+        }
+    }
+
+    fn get_text(&self, locale: &LocaleHandle) -> String {
+        match self {
+            LimitReason::Ocpp => locale.translate("limit-ocpp", "OCPP smart charging"),
+            LimitReason::Grid => locale.translate("limit-grid", "Grid limit"),
+        }
+    }
+}
+
+struct LimitData {
+    evse_imax: u32,
+    cable_imax: u32,
+    limit: Option<u32>,
+    reason: Option<LimitReason>,
+    expire: Option<Duration>,
+}
+
+impl LimitData {
+    fn get_imax(&self) -> u32 {
+        self.evse_imax.min(self.cable_imax)
+    }
+
+    fn release(&mut self) {
+        self.limit = None;
+        self.reason = None;
+        self.expire = None;
+    }
+
+    // a limit at or over cable/EVSE max is no limit
+    fn set_limit(&mut self, limit: &PowerLimit, now: Duration) {
+        if limit.imax < 0 || limit.imax as u32 >= self.get_imax() {
+            self.release();
+        } else {
+            self.limit = Some(limit.imax as u32);
+            self.reason = Some(LimitReason::from_limit(limit));
+            self.expire = match limit.duration {
+                0 => None,
+                duration => Some(now + Duration::from_secs(duration as u64)),
+            };
+        }
+    }
+
+    // return remaining time, None when no limit expires (released at expiration)
+    fn expire(&mut self, now: Duration) -> Option<Duration> {
+        let expire = self.expire?;
+        if now >= expire {
+            self.release();
+            None
+        } else {
+            Some(expire - now)
+        }
+    }
+}
+
+// smart charging current limit (grid or OCPP) against cable/EVSE max
+pub struct LimitPanel {
+    reason: &'static LvglLabel,
+    bar: &'static LvglBar,
+    value: &'static LvglLabel,
+    remaining: &'static LvglLabel,
+    locale: &'static LocaleHandle,
+    data_set: Mutex<LimitData>,
+    dispatch: &'static LvglDispatch,
+}
+
+impl LimitPanel {
+    pub fn new(
+        display: &DisplayHandle,
+        evse_imax: u32,
+        cable_imax: u32,
+        locale: &'static LocaleHandle,
+    ) -> Result<&'static Self, AfbError> {
+        let panel = LimitPanel {
+            reason: get_widget::<LvglLabel>(display, "LimitReason")?,
+            bar: get_widget::<LvglBar>(display, "LimitBar")?,
+            value: get_widget::<LvglLabel>(display, "LimitVal")?,
+            remaining: get_widget::<LvglLabel>(display, "LimitRemaining")?,
+            locale,
+            data_set: Mutex::new(LimitData {
+                evse_imax,
+                cable_imax,
+                limit: None,
+                reason: None,
+                expire: None,
+            }),
+            dispatch: display.get_dispatch(),
+        };
+        let panel: &'static LimitPanel = Box::leak(Box::new(panel));
+        panel.refresh(&panel.get_state()?);
+        locale.add_listener(panel)?;
+        Ok(panel)
+    }
+
+    fn get_state(&self) -> Result<MutexGuard<'_, LimitData>, AfbError> {
+        match self.data_set.lock() {
+            Ok(guard) => Ok(guard),
+            Err(_) => afb_error!("limit-state-lock", "fail to lock power limit state"),
+        }
+    }
+
+    fn set_label(&'static self, label: &'static LvglLabel, text: &str) {
+        let text = text.to_string();
+        self.dispatch.post(move || {
+            label.set_value(text.as_str());
+        });
+    }
+
+    fn refresh(&'static self, state: &LimitData) {
+        let imax = state.get_imax();
+        let limit = state.limit;
+        let reason = match state.reason {
+            Some(reason) => reason.get_text(self.locale),
+            None => String::new(),
+        };
+        self.dispatch.post(move || {
+            self.bar.set_range(0, imax as i32);
+            self.reason.set_value(reason.as_str());
+            match limit {
+                Some(limit) => {
+                    self.bar.set_value(limit as i32);
+                    self.value.set_value(format!("{}A / {}A", limit, imax).as_str());
+                }
+                None => {
+                    self.bar.set_value(imax as i32);
+                    self.value.set_value(format!("{}A", imax).as_str());
+                    self.remaining.set_value("");
+                }
+            }
+        });
+    }
+
+    // EVSE max comes from charging manager state
+    pub fn set_evse_imax(&'static self, imax: u32) -> Result<(), AfbError> {
+        let mut state = self.get_state()?;
+        state.evse_imax = imax;
+        self.refresh(&state);
+        Ok(())
+    }
+
+    pub fn set_limit(&'static self, limit: &PowerLimit) -> Result<(), AfbError> {
+        let mut state = self.get_state()?;
+        state.set_limit(limit, time_now());
+        self.set_label(self.remaining, "");
+        self.refresh(&state);
+        Ok(())
+    }
+
+    // called every second, limit is released at expiration
+    pub fn tick(&'static self) -> Result<(), AfbError> {
+        let mut state = self.get_state()?;
+        if state.expire.is_none() {
+            return Ok(());
+        }
+        match state.expire(time_now()) {
+            Some(remaining) => self.set_label(self.remaining, fmt_duration(remaining).as_str()),
+            None => self.refresh(&state),
+        }
+        Ok(())
+    }
+}
+
+impl LocaleListener for LimitPanel {
+    fn locale_changed(&'static self) {
+        if let Ok(state) = self.get_state() {
+            self.refresh(&state);
+        }
+    }
+}
+
+// grid headroom and over-current warning, meter is optional
+pub struct GridPanel {
+    value: &'static LvglLabel,
+    overlay: &'static LvglOverlay,
+    dispatch: &'static LvglDispatch,
+}
+
+impl GridPanel {
+    pub fn new(display: &DisplayHandle) -> Result<&'static Self, AfbError> {
+        let panel = GridPanel {
+            value: get_widget::<LvglLabel>(display, "GridVal")?,
+            overlay: get_widget::<LvglOverlay>(display, "Overlay-overcurrent")?,
+            dispatch: display.get_dispatch(),
+        };
+        Ok(Box::leak(Box::new(panel)))
+    }
+
+    // meter values are in 00.A (1/1000) units
+    pub fn set_meter(&'static self, data: &MeterDataSet) {
+        match data.tag {
+            MeterTagSet::AvailCurrent => {
+                let text = format!("{:.1}A", data.total as f64 / 1000.0);
+                self.dispatch.post(move || {
+                    self.value.set_value(text.as_str());
+                });
+            }
+            MeterTagSet::OverCurrent => {
+                let hidden = data.total <= 0;
+                self.dispatch.post(move || {
+                    self.overlay.set_hidden(hidden);
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+// accepted badge details, pmax from auth is in kW (22 by default)
+pub struct AuthPanel {
+    tagid: &'static LvglLabel,
+    limits: &'static LvglLabel,
+    mode: &'static LvglLabel,
+    locale: &'static LocaleHandle,
+    // masked badge, limits and ocpp check, kept to redraw on language change
+    shown: Mutex<Option<(String, String, bool)>>,
+    dispatch: &'static LvglDispatch,
+}
+
+impl AuthPanel {
+    pub fn new(display: &DisplayHandle, locale: &'static LocaleHandle) -> Result<&'static Self, AfbError> {
+        let panel = AuthPanel {
+            tagid: get_widget::<LvglLabel>(display, "AuthTag")?,
+            limits: get_widget::<LvglLabel>(display, "AuthLimits")?,
+            mode: get_widget::<LvglLabel>(display, "AuthMode")?,
+            locale,
+            shown: Mutex::new(None),
+            dispatch: display.get_dispatch(),
+        };
+        let panel: &'static AuthPanel = Box::leak(Box::new(panel));
+        locale.add_listener(panel)?;
+        Ok(panel)
+    }
+
+    pub fn set_state(&'static self, state: &AuthState) {
+        match state.auth {
+            AuthMsg::Done => {
+                let shown = (
+                    mask_tagid(state.tagid.as_str()),
+                    format!("{}A / {}kW", state.imax, state.pmax),
+                    state.ocpp_check,
+                );
+                self.draw(&shown);
+                if let Ok(mut value) = self.shown.lock() {
+                    *value = Some(shown);
+                }
+            }
+            _ => self.clear(),
+        }
+    }
+
+    fn draw(&'static self, shown: &(String, String, bool)) {
+        let (tagid, limits, ocpp_check) = shown;
+        let tagid = format!("{} {}", self.locale.translate("badge", "Badge"), tagid);
+        let limits = limits.clone();
+        let mode = if *ocpp_check {
+            self.locale.translate("auth-ocpp", "OCPP validated")
+        } else {
+            self.locale.translate("auth-local", "Local authorisation")
+        };
+        self.dispatch.post(move || {
+            self.tagid.set_value(tagid.as_str());
+            self.limits.set_value(limits.as_str());
+            self.mode.set_value(mode.as_str());
+        });
+    }
+
+    pub fn clear(&'static self) {
+        if let Ok(mut value) = self.shown.lock() {
+            *value = None;
+        }
+        self.dispatch.post(move || {
+            self.tagid.set_value("");
+            self.limits.set_value("");
+            self.mode.set_value("");
+        });
+    }
+}
+
+impl LocaleListener for AuthPanel {
+    fn locale_changed(&'static self) {
+        let shown = match self.shown.lock() {
+            Ok(value) => value.clone(),
+            Err(_) => None,
+        };
+        if let Some(shown) = shown {
+            self.draw(&shown);
+        }
+    }
+}
+
+struct QrcodeData {
+    template: String,
+    payload: String,
+}
+
+// bottom qrcode, payload is regenerated when template or session changes
+pub struct QrcodePanel {
+    qrcode: &'static LvglQrcode,
+    config: QrcodeConfig,
+    session: &'static SessionHandle,
+    data_set: Mutex<QrcodeData>,
+    dispatch: &'static LvglDispatch,
+}
+
+impl QrcodePanel {
+    pub fn new(
+        display: &DisplayHandle,
+        config: QrcodeConfig,
+        session: &'static SessionHandle,
+    ) -> Result<&'static Self, AfbError> {
+        let panel = QrcodePanel {
+            qrcode: get_widget::<LvglQrcode>(display, "qr-code")?,
+            data_set: Mutex::new(QrcodeData {
+                template: config.payload.clone(),
+                payload: String::new(),
+            }),
+            config,
+            session,
+            dispatch: display.get_dispatch(),
+        };
+        let panel: &'static QrcodePanel = Box::leak(Box::new(panel));
+        panel.refresh()?;
+        Ok(panel)
+    }
+
+    fn get_state(&self) -> Result<MutexGuard<'_, QrcodeData>, AfbError> {
+        match self.data_set.lock() {
+            Ok(guard) => Ok(guard),
+            Err(_) => afb_error!("qrcode-state-lock", "fail to lock qrcode state"),
+        }
+    }
+
+    pub fn set_template(&'static self, template: &str) -> Result<(), AfbError> {
+        self.get_state()?.template = template.to_string();
+        self.refresh()
+    }
+
+    // called when session id may change (start, stop, plug-in, energy state), unchanged payload is not redrawn
+    pub fn refresh(&'static self) -> Result<(), AfbError> {
+        let session_id = self.session.get_session_id()?;
+        let mut state = self.get_state()?;
+        let payload = self.config.fill(state.template.as_str(), session_id.as_str());
+        if payload != state.payload {
+            state.payload = payload.clone();
+            self.dispatch.post(move || {
+                self.qrcode.set_value(payload.as_str());
+            });
+        }
+        Ok(())
+    }
+}
+
+struct NetworkData {
+    ethernet: Option<bool>,
+    wifi: Option<bool>,
+    level: Option<WifiLevel>,
+}
+
+// menu network icons, only changes are redrawn
+pub struct NetworkPanel {
+    ethernet: &'static LvglPixmap,
+    wifi: &'static LvglPixmap,
+    level: &'static LvglPixmap,
+    config: NetworkConfig,
+    data_set: Mutex<NetworkData>,
+    dispatch: &'static LvglDispatch,
+}
+
+impl NetworkPanel {
+    pub fn new(display: &DisplayHandle, config: NetworkConfig) -> Result<&'static Self, AfbError> {
+        let panel = NetworkPanel {
+            ethernet: get_widget::<LvglPixmap>(display, "Pixmap-net")?,
+            wifi: get_widget::<LvglPixmap>(display, "Pixmap-wifi")?,
+            level: get_widget::<LvglPixmap>(display, "Pixmap-wifi_level")?,
+            config,
+            data_set: Mutex::new(NetworkData {
+                ethernet: None,
+                wifi: None,
+                level: None,
+            }),
+            dispatch: display.get_dispatch(),
+        };
+        Ok(Box::leak(Box::new(panel)))
+    }
+
+    fn get_state(&self) -> Result<MutexGuard<'_, NetworkData>, AfbError> {
+        match self.data_set.lock() {
+            Ok(guard) => Ok(guard),
+            Err(_) => afb_error!("network-state-lock", "fail to lock network state"),
+        }
+    }
+
+    // called from network timer
+    pub fn poll(&'static self) -> Result<(), AfbError> {
+        let ethernet = link_is_up(self.config.ethernet.as_str());
+        let wifi = link_is_up(self.config.wifi.as_str());
+        let level = if wifi {
+            wifi_level(self.config.wifi.as_str())
+        } else {
+            WifiLevel::None
+        };
+
+        let mut state = self.get_state()?;
+        if state.ethernet != Some(ethernet) {
+            state.ethernet = Some(ethernet);
+            self.dispatch.post(move || {
+                self.ethernet.set_value(if ethernet {
+                    AssetPixmap::ethernet_on()
+                } else {
+                    AssetPixmap::ethernet_off()
+                });
+            });
+        }
+        if state.wifi != Some(wifi) {
+            state.wifi = Some(wifi);
+            self.dispatch.post(move || {
+                self.wifi.set_value(if wifi {
+                    AssetPixmap::wifi_on()
+                } else {
+                    AssetPixmap::wifi_off()
+                });
+            });
+        }
+        // no intermediate reception asset, weak signal is drawn greyed out
+        if state.level != Some(level) {
+            state.level = Some(level);
+            self.dispatch.post(move || {
+                match level {
+                    WifiLevel::None => {
+                        self.level.set_value(AssetPixmap::reception_off());
+                        self.level.set_dimmed(false);
+                    }
+                    WifiLevel::Weak => {
+                        self.level.set_value(AssetPixmap::reception_on());
+                        self.level.set_dimmed(true);
+                    }
+                    WifiLevel::Good => {
+                        self.level.set_value(AssetPixmap::reception_on());
+                        self.level.set_dimmed(false);
+                    }
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit_data(evse_imax: u32, cable_imax: u32) -> LimitData {
+        LimitData {
+            evse_imax,
+            cable_imax,
+            limit: None,
+            reason: None,
+            expire: None,
+        }
+    }
+
+    fn power_limit(tid: i32, imax: i32, duration: u32) -> PowerLimit {
+        PowerLimit { tid, imax, duration }
+    }
+
+    #[test]
+    fn limit_clamped_to_cable_and_evse() {
+        let now = Duration::from_secs(1000);
+        let mut data = limit_data(32, 20);
+        data.set_limit(&power_limit(0, 16, 0), now);
+        assert_eq!(data.limit, Some(16));
+        assert_eq!(data.reason, Some(LimitReason::Grid));
+        assert_eq!(data.expire, None);
+
+        // at or over cable max is no limit
+        data.set_limit(&power_limit(0, 20, 0), now);
+        assert_eq!(data.limit, None);
+        assert_eq!(data.reason, None);
+
+        // evse max lower than cable
+        let mut data = limit_data(10, 32);
+        data.set_limit(&power_limit(5, 12, 0), now);
+        assert_eq!(data.limit, None);
+        data.set_limit(&power_limit(5, 8, 0), now);
+        assert_eq!(data.limit, Some(8));
+        assert_eq!(data.reason, Some(LimitReason::Ocpp));
+
+        // negative is a release
+        data.set_limit(&power_limit(5, -1, 0), now);
+        assert_eq!(data.limit, None);
+    }
+
+    #[test]
+    fn limit_expires_after_duration() {
+        let mut data = limit_data(32, 32);
+        data.set_limit(&power_limit(7, 10, 60), Duration::from_secs(1000));
+        assert_eq!(data.expire(Duration::from_secs(1020)), Some(Duration::from_secs(40)));
+        assert_eq!(data.limit, Some(10));
+
+        assert_eq!(data.expire(Duration::from_secs(1060)), None);
+        assert_eq!(data.limit, None);
+        assert_eq!(data.reason, None);
+        assert_eq!(data.expire, None);
+
+        // no duration never expires
+        data.set_limit(&power_limit(0, 10, 0), Duration::from_secs(1000));
+        assert_eq!(data.expire(Duration::from_secs(100_000)), None);
+        assert_eq!(data.limit, Some(10));
+    }
+}
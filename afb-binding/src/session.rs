@@ -0,0 +1,566 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ */
+
+use crate::prelude::*;
+use afbv4::prelude::*;
+use display_lvgl_gui::prelude::*;
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub fn time_now() -> Duration {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(value) => value,
+        Err(_) => Duration::new(0, 0),
+    }
+}
+
+// hh:mm:ss display format
+pub fn fmt_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        (seconds / 60) % 60,
+        seconds % 60
+    )
+}
+
+// local time minute of day, used to select tariff bands
+pub fn local_minutes() -> u32 {
+    match get_time("%H:%M") {
+        Ok(value) => {
+            let mut parts = value.split(':');
+            let hours = parts.next().and_then(|hour| hour.parse::<u32>().ok()).unwrap_or(0);
+            let minutes = parts.next().and_then(|min| min.parse::<u32>().ok()).unwrap_or(0);
+            hours * 60 + minutes
+        }
+        Err(_) => 0,
+    }
+}
+
+// only keep the last 4 characters of badge id
+pub fn mask_tagid(tagid: &str) -> String {
+    let count = tagid.chars().count();
+    if count == 0 {
+        return "--".to_string();
+    }
+    let visible = if count > 4 { 4 } else { count / 2 };
+    let hidden: String = "*".repeat(count - visible);
+    let tail: String = tagid.chars().skip(count - visible).collect();
+    format!("{}{}", hidden, tail)
+}
+
+pub struct SessionConfig {
+    pub window: u32, // rolling chart window in seconds
+    pub points: u32, // max chart points within window
+    pub tariff: Option<Tariff>,
+    pub summary_timeout: u32, // summary screen timeout after plug-out in seconds
+}
+
+pub struct SessionSummary {
+    pub energy: i32, // Wh
+    pub duration: Duration,
+    pub avg_power: i32,  // W
+    pub peak_power: i32, // W
+    pub cost: Option<f64>,
+    pub tagid: String, // masked
+    pub stopped: Duration,
+}
+
+impl SessionSummary {
+    // compact text encoded within receipt qrcode
+    pub fn get_receipt(&self, currency: Option<&str>) -> String {
+        let mut receipt = format!(
+            "EVSE-RECEIPT;stop={};tag={};energy={:.3}kWh;duration={};avg={}W;peak={}W",
+            self.stopped.as_secs(),
+            self.tagid,
+            self.energy as f64 / 1000.0,
+            fmt_duration(self.duration),
+            self.avg_power,
+            self.peak_power,
+        );
+        if let (Some(cost), Some(currency)) = (self.cost, currency) {
+            receipt.push_str(format!(";cost={:.2}{}", cost, currency).as_str());
+        }
+        receipt
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct PowerSample {
+    pub stamp: Duration,
+    pub power: i32,   // W
+    pub current: i32, // 0.1A
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SessionStatus {
+    Idle,
+    Running,
+    Suspended,
+    Stopped,
+}
+
+pub struct SessionState {
+    pub status: SessionStatus,
+    pub started: Duration,
+    pub paused_at: Duration,
+    pub paused_total: Duration,
+    pub stopped_at: Duration,
+    pub current: i32,
+    pub samples: VecDeque<PowerSample>,
+    pub energy: i32, // Wh, charged during session
+    pub meter_last: Option<i32>, // Wh, last meter reading, None until first event of session
+    pub energy_cost: f64,
+    pub time_cost: f64,
+    pub cost_elapsed: Duration,
+    pub peak_power: i32,
+    pub tagid: String,
+    pub summary_deadline: Option<Duration>,
+    pub unplugged: bool,
+    pub engy_session: Option<i32>, // energy manager session id, read at session start
+    pub transaction: Option<i32>, // OCPP transaction id from chmgr power limits
+}
+
+impl SessionState {
+    // charging time excluding suspended periods
+    pub fn get_elapsed(&self) -> Duration {
+        let now = match self.status {
+            SessionStatus::Idle => return Duration::new(0, 0),
+            SessionStatus::Stopped => self.stopped_at,
+            SessionStatus::Suspended => self.paused_at,
+            SessionStatus::Running => time_now(),
+        };
+        now.saturating_sub(self.started)
+            .saturating_sub(self.paused_total)
+    }
+}
+
+pub struct SessionHandle {
+    config: SessionConfig,
+    data_set: Mutex<SessionState>,
+}
+
+impl SessionHandle {
+    pub fn new(config: SessionConfig) -> &'static Self {
+        let handle = SessionHandle {
+            config,
+            data_set: Mutex::new(SessionState {
+                status: SessionStatus::Idle,
+                started: Duration::new(0, 0),
+                paused_at: Duration::new(0, 0),
+                paused_total: Duration::new(0, 0),
+                stopped_at: Duration::new(0, 0),
+                current: 0,
+                samples: VecDeque::new(),
+                energy: 0,
+                meter_last: None,
+                energy_cost: 0.0,
+                time_cost: 0.0,
+                cost_elapsed: Duration::new(0, 0),
+                peak_power: 0,
+                tagid: String::new(),
+                summary_deadline: None,
+                unplugged: false,
+                engy_session: None,
+                transaction: None,
+            }),
+        };
+        Box::leak(Box::new(handle))
+    }
+
+    pub fn get_state(&self) -> Result<MutexGuard<'_, SessionState>, AfbError> {
+        match self.data_set.lock() {
+            Ok(guard) => Ok(guard),
+            Err(_) => afb_error!("session-state-lock", "fail to lock session state"),
+        }
+    }
+
+    // new session (PowerRequest::Start) flush previous curve and reset timer
+    pub fn start(&self) -> Result<(), AfbError> {
+        let mut state = self.get_state()?;
+        Self::start_locked(&mut state);
+        Ok(())
+    }
+
+    // caller holds state lock
+    fn start_locked(state: &mut SessionState) {
+        state.status = SessionStatus::Running;
+        state.started = time_now();
+        state.paused_total = Duration::new(0, 0);
+        state.samples.clear();
+        state.energy = 0;
+        state.meter_last = None;
+        state.energy_cost = 0.0;
+        state.time_cost = 0.0;
+        state.cost_elapsed = Duration::new(0, 0);
+        state.peak_power = 0;
+        state.summary_deadline = None;
+        state.engy_session = None;
+    }
+
+    // PowerRequest::Charging(0) is considered as a suspended session, check and update under one lock
+    pub fn charging(&self, active: bool) -> Result<(), AfbError> {
+        let mut state = self.get_state()?;
+        match state.status {
+            SessionStatus::Idle | SessionStatus::Stopped => {
+                if active {
+                    Self::start_locked(&mut state);
+                }
+            }
+            SessionStatus::Running => {
+                if !active {
+                    state.status = SessionStatus::Suspended;
+                    state.paused_at = time_now();
+                }
+            }
+            SessionStatus::Suspended => {
+                if active {
+                    let paused = time_now().saturating_sub(state.paused_at);
+                    state.paused_total += paused;
+                    state.status = SessionStatus::Running;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // session done (PowerRequest::Stop) keep curve and timer as they are
+    pub fn stop(&self) -> Result<(), AfbError> {
+        let mut state = self.get_state()?;
+        match state.status {
+            SessionStatus::Running => {
+                state.stopped_at = time_now();
+            }
+            SessionStatus::Suspended => {
+                state.stopped_at = state.paused_at;
+            }
+            _ => {}
+        }
+        state.status = SessionStatus::Stopped;
+
+        // plug was removed before chmgr stop, summary has to time out by itself
+        if state.unplugged && state.summary_deadline.is_none() {
+            state.summary_deadline = Some(time_now() + self.get_summary_timeout());
+        }
+        Ok(())
+    }
+
+    // display restarted while charging, session start comes from EnergyState.timestamp
+    pub fn restore(&self, timestamp: Duration) -> Result<(), AfbError> {
+        let mut state = self.get_state()?;
+        let now = time_now();
+        if timestamp.is_zero() || timestamp > now {
+            return Ok(());
+        }
+        if state.status == SessionStatus::Idle {
+            state.status = SessionStatus::Running;
+            state.paused_total = Duration::new(0, 0);
+        }
+        state.started = timestamp;
+        Ok(())
+    }
+
+    pub fn get_elapsed(&self) -> Result<Duration, AfbError> {
+        Ok(self.get_state()?.get_elapsed())
+    }
+
+    // energy manager session id, else OCPP transaction id from power limits, empty when no session
+    pub fn get_session_id(&self) -> Result<String, AfbError> {
+        let state = self.get_state()?;
+        let session_id = match (state.status, state.engy_session.or(state.transaction)) {
+            (SessionStatus::Idle, _) | (_, None) => String::new(),
+            (_, Some(id)) => id.to_string(),
+        };
+        Ok(session_id)
+    }
+
+    // EnergyState.session (0 when none)
+    pub fn set_engy_session(&self, session: i32) -> Result<(), AfbError> {
+        if session > 0 {
+            self.get_state()?.engy_session = Some(session);
+        }
+        Ok(())
+    }
+
+    // chmgr limits within a transaction carry its id (0 when none)
+    pub fn set_transaction(&self, tid: i32) -> Result<(), AfbError> {
+        if tid > 0 {
+            self.get_state()?.transaction = Some(tid);
+        }
+        Ok(())
+    }
+
+    pub fn get_tariff(&self) -> Option<&Tariff> {
+        self.config.tariff.as_ref()
+    }
+
+    // cumulative energy meter in Wh, first event of a session is the baseline,
+    // then the delta since last event is charged at current band price
+    pub fn set_energy(&self, meter: i32) -> Result<(), AfbError> {
+        let mut state = self.get_state()?;
+        if !matches!(state.status, SessionStatus::Running | SessionStatus::Suspended) {
+            return Ok(());
+        }
+        let delta = match state.meter_last {
+            None => 0,
+            Some(last) if meter >= last => meter - last,
+            Some(_) => meter, // meter was reset
+        };
+        state.meter_last = Some(meter);
+        state.energy += delta;
+        if let Some(tariff) = &self.config.tariff {
+            let (price_kwh, _) = tariff.get_rates(local_minutes());
+            state.energy_cost += delta as f64 / 1000.0 * price_kwh;
+        }
+        Ok(())
+    }
+
+    // called from session timer, charge elapsed charging time since last call
+    pub fn update_cost(&self) -> Result<(), AfbError> {
+        let mut state = self.get_state()?;
+        let elapsed = state.get_elapsed();
+        let delta = elapsed.saturating_sub(state.cost_elapsed);
+        state.cost_elapsed = elapsed;
+        if let Some(tariff) = &self.config.tariff {
+            let (_, price_minute) = tariff.get_rates(local_minutes());
+            state.time_cost += delta.as_secs_f64() / 60.0 * price_minute;
+        }
+        Ok(())
+    }
+
+    // None when no tariff is configured
+    pub fn get_cost(&self) -> Result<Option<f64>, AfbError> {
+        let state = self.get_state()?;
+        let cost = match &self.config.tariff {
+            None => None,
+            Some(tariff) => match state.status {
+                SessionStatus::Idle => Some(0.0),
+                _ => Some(tariff.session_fee + state.energy_cost + state.time_cost),
+            },
+        };
+        Ok(cost)
+    }
+
+    // meter values are in 00.A (1/1000) units
+    pub fn set_current(&self, meter: i32) -> Result<(), AfbError> {
+        let mut state = self.get_state()?;
+        state.current = meter / 100;
+        Ok(())
+    }
+
+    // push a new power sample and return false when session curve is frozen
+    pub fn push_power(&self, meter: i32) -> Result<bool, AfbError> {
+        let mut state = self.get_state()?;
+        if state.status == SessionStatus::Stopped {
+            return Ok(false);
+        }
+        let stamp = time_now();
+        let sample = PowerSample {
+            stamp,
+            power: meter / 1000,
+            current: state.current,
+        };
+        state.samples.push_back(sample);
+        if sample.power > state.peak_power {
+            state.peak_power = sample.power;
+        }
+
+        // drop samples out of rolling window or over chart capacity
+        let window = Duration::from_secs(self.config.window as u64);
+        while let Some(first) = state.samples.front() {
+            if stamp.saturating_sub(first.stamp) > window
+                || state.samples.len() > self.config.points as usize
+            {
+                state.samples.pop_front();
+            } else {
+                break;
+            }
+        }
+        Ok(true)
+    }
+
+    // badge used for the session, received from auth state
+    pub fn set_tagid(&self, tagid: &str) -> Result<(), AfbError> {
+        let mut state = self.get_state()?;
+        state.tagid = tagid.to_string();
+        Ok(())
+    }
+
+    pub fn get_summary(&self) -> Result<SessionSummary, AfbError> {
+        let cost = self.get_cost()?;
+        let state = self.get_state()?;
+        let duration = state.get_elapsed();
+        let avg_power = if duration.as_secs() > 0 {
+            (state.energy as f64 * 3600.0 / duration.as_secs_f64()) as i32
+        } else {
+            0
+        };
+        Ok(SessionSummary {
+            energy: state.energy,
+            duration,
+            avg_power,
+            peak_power: state.peak_power,
+            cost,
+            tagid: mask_tagid(state.tagid.as_str()),
+            stopped: state.stopped_at,
+        })
+    }
+
+    fn get_summary_timeout(&self) -> Duration {
+        Duration::from_secs(self.config.summary_timeout as u64)
+    }
+
+    // new plug-in, summary stays up to next plug-out and previous transaction is over
+    pub fn plug_in(&self) -> Result<(), AfbError> {
+        let mut state = self.get_state()?;
+        state.unplugged = false;
+        state.engy_session = None;
+        state.transaction = None;
+        Ok(())
+    }
+
+    // summary screen stays until plug-out, then times out (armed by stop() when plug-out came first)
+    pub fn arm_summary_timeout(&self) -> Result<(), AfbError> {
+        let mut state = self.get_state()?;
+        state.unplugged = true;
+        if state.status == SessionStatus::Stopped && state.summary_deadline.is_none() {
+            state.summary_deadline = Some(time_now() + self.get_summary_timeout());
+        }
+        Ok(())
+    }
+
+    // return true once when summary deadline is reached
+    pub fn check_summary_timeout(&self) -> Result<bool, AfbError> {
+        let mut state = self.get_state()?;
+        match state.summary_deadline {
+            Some(deadline) if time_now() >= deadline => {
+                state.summary_deadline = None;
+                state.tagid.clear();
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    pub fn get_curve(&self) -> Result<(Vec<i16>, Vec<i16>), AfbError> {
+        let state = self.get_state()?;
+        let power = state
+            .samples
+            .iter()
+            .map(|sample| sample.power.clamp(0, i16::MAX as i32) as i16)
+            .collect();
+        let current = state
+            .samples
+            .iter()
+            .map(|sample| sample.current.clamp(0, i16::MAX as i32) as i16)
+            .collect();
+        Ok((power, current))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // flat rate, band selection does not depend on local time
+    fn session(summary_timeout: u32) -> &'static SessionHandle {
+        SessionHandle::new(SessionConfig {
+            window: 3600,
+            points: 100,
+            tariff: Some(Tariff {
+                currency: "EUR".to_string(),
+                session_fee: 1.0,
+                price_kwh: 0.5,
+                price_minute: 0.0,
+                bands: Vec::new(),
+            }),
+            summary_timeout,
+        })
+    }
+
+    fn energy_cost(session: &SessionHandle) -> (i32, f64) {
+        let state = session.get_state().unwrap();
+        (state.energy, state.energy_cost)
+    }
+
+    #[test]
+    fn first_reading_is_baseline() {
+        let session = session(60);
+        session.start().unwrap();
+        session.set_energy(12_000).unwrap();
+        assert_eq!(energy_cost(session), (0, 0.0));
+
+        session.set_energy(14_000).unwrap();
+        let (energy, cost) = energy_cost(session);
+        assert_eq!(energy, 2000);
+        assert!((cost - 1.0).abs() < 1e-9, "cost:{}", cost);
+        assert!((session.get_cost().unwrap().unwrap() - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn meter_reset_counts_from_zero() {
+        let session = session(60);
+        session.start().unwrap();
+        session.set_energy(5000).unwrap();
+        session.set_energy(6000).unwrap();
+        // meter restarted from 0
+        session.set_energy(500).unwrap();
+        let (energy, cost) = energy_cost(session);
+        assert_eq!(energy, 1500);
+        assert!((cost - 0.75).abs() < 1e-9, "cost:{}", cost);
+    }
+
+    #[test]
+    fn energy_only_counted_while_charging() {
+        let session = session(60);
+        session.set_energy(1000).unwrap();
+        session.set_energy(2000).unwrap();
+        assert_eq!(energy_cost(session), (0, 0.0));
+        assert_eq!(session.get_cost().unwrap(), Some(0.0));
+
+        session.start().unwrap();
+        session.set_energy(2000).unwrap();
+        session.charging(false).unwrap();
+        session.set_energy(2500).unwrap();
+        assert_eq!(energy_cost(session).0, 500);
+
+        session.stop().unwrap();
+        session.set_energy(4000).unwrap();
+        assert_eq!(energy_cost(session).0, 500);
+
+        // next session restarts from a new baseline
+        session.start().unwrap();
+        session.set_energy(4000).unwrap();
+        assert_eq!(energy_cost(session), (0, 0.0));
+    }
+
+    #[test]
+    fn summary_timeout_with_plug_out_before_stop() {
+        let session = session(0);
+        session.start().unwrap();
+        session.arm_summary_timeout().unwrap();
+        assert!(!session.check_summary_timeout().unwrap());
+
+        session.stop().unwrap();
+        assert!(session.check_summary_timeout().unwrap());
+        assert!(!session.check_summary_timeout().unwrap());
+    }
+
+    #[test]
+    fn tagid_masking() {
+        assert_eq!(mask_tagid(""), "--");
+        assert_eq!(mask_tagid("AB"), "*B");
+        assert_eq!(mask_tagid("04A2B3C4"), "****B3C4");
+    }
+}
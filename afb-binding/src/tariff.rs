@@ -0,0 +1,153 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ */
+
+use afbv4::prelude::*;
+use serde::Deserialize;
+
+// time-of-day band, start/stop as "HH:MM" local time, stop<start wraps over midnight
+#[derive(Deserialize, Debug, Clone)]
+pub struct TariffBand {
+    pub start: String,
+    pub stop: String,
+    pub price_kwh: Option<f64>,
+    pub price_minute: Option<f64>,
+}
+
+// binding config 'tariff' object
+#[derive(Deserialize, Debug, Clone)]
+pub struct Tariff {
+    pub currency: String,
+    #[serde(default)]
+    pub session_fee: f64,
+    #[serde(default)]
+    pub price_kwh: f64,
+    #[serde(default)]
+    pub price_minute: f64,
+    #[serde(default)]
+    pub bands: Vec<TariffBand>,
+}
+
+// 'HH:MM' to minute of day, shared with theme schedule
+pub fn parse_minutes(value: &str) -> Result<u32, AfbError> {
+    let mut parts = value.split(':');
+    let hours = parts.next().and_then(|hour| hour.trim().parse::<u32>().ok());
+    let minutes = parts.next().and_then(|min| min.trim().parse::<u32>().ok());
+    match (hours, minutes) {
+        (Some(hours), Some(minutes)) if hours < 24 && minutes < 60 => Ok(hours * 60 + minutes),
+        _ => afb_error!("tariff-band-time", "invalid band time:'{}' expect HH:MM", value),
+    }
+}
+
+impl Tariff {
+    pub fn from_jsonc(jtariff: JsoncObj) -> Result<Self, AfbError> {
+        let tariff = match serde_json::from_str::<Tariff>(jtariff.to_string().as_str()) {
+            Ok(value) => value,
+            Err(error) => return afb_error!("tariff-config-fail", "invalid tariff config:{}", error),
+        };
+        // check band format at config time
+        for band in &tariff.bands {
+            parse_minutes(band.start.as_str())?;
+            parse_minutes(band.stop.as_str())?;
+        }
+        Ok(tariff)
+    }
+
+    // return (price/kWh, price/minute) applicable at given local minute of day
+    pub fn get_rates(&self, minute_of_day: u32) -> (f64, f64) {
+        for band in &self.bands {
+            let (start, stop) = match (
+                parse_minutes(band.start.as_str()),
+                parse_minutes(band.stop.as_str()),
+            ) {
+                (Ok(start), Ok(stop)) => (start, stop),
+                _ => continue,
+            };
+            // stop is excluded, start equal to stop is an empty band, start after stop wraps midnight
+            let inside = if start <= stop {
+                minute_of_day >= start && minute_of_day < stop
+            } else {
+                minute_of_day >= start || minute_of_day < stop
+            };
+            if inside {
+                return (
+                    band.price_kwh.unwrap_or(self.price_kwh),
+                    band.price_minute.unwrap_or(self.price_minute),
+                );
+            }
+        }
+        (self.price_kwh, self.price_minute)
+    }
+
+    pub fn fmt_cost(&self, cost: f64) -> String {
+        format!("{:.2} {}", cost, self.currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn band(start: &str, stop: &str, price_kwh: f64) -> TariffBand {
+        TariffBand {
+            start: start.to_string(),
+            stop: stop.to_string(),
+            price_kwh: Some(price_kwh),
+            price_minute: None,
+        }
+    }
+
+    fn tariff(bands: Vec<TariffBand>) -> Tariff {
+        Tariff {
+            currency: "EUR".to_string(),
+            session_fee: 0.0,
+            price_kwh: 0.30,
+            price_minute: 0.02,
+            bands,
+        }
+    }
+
+    #[test]
+    fn time_format() {
+        assert_eq!(parse_minutes("00:00").unwrap(), 0);
+        assert_eq!(parse_minutes("22:30").unwrap(), 22 * 60 + 30);
+        assert!(parse_minutes("24:00").is_err());
+        assert!(parse_minutes("12:60").is_err());
+        assert!(parse_minutes("12").is_err());
+    }
+
+    #[test]
+    fn overnight_band_wraps_midnight() {
+        let tariff = tariff(vec![band("22:00", "06:00", 0.15)]);
+        assert_eq!(tariff.get_rates(23 * 60), (0.15, 0.02));
+        assert_eq!(tariff.get_rates(0), (0.15, 0.02));
+        assert_eq!(tariff.get_rates(5 * 60 + 59), (0.15, 0.02));
+        // stop is excluded
+        assert_eq!(tariff.get_rates(6 * 60), (0.30, 0.02));
+        assert_eq!(tariff.get_rates(12 * 60), (0.30, 0.02));
+        assert_eq!(tariff.get_rates(22 * 60), (0.15, 0.02));
+    }
+
+    #[test]
+    fn empty_band_never_applies() {
+        let tariff = tariff(vec![band("08:00", "08:00", 0.10)]);
+        for minute in [0, 8 * 60, 8 * 60 + 1, 20 * 60] {
+            assert_eq!(tariff.get_rates(minute), (0.30, 0.02));
+        }
+    }
+
+    #[test]
+    fn first_matching_band_wins() {
+        let tariff = tariff(vec![band("08:00", "12:00", 0.40), band("00:00", "23:59", 0.20)]);
+        assert_eq!(tariff.get_rates(9 * 60), (0.40, 0.02));
+        assert_eq!(tariff.get_rates(13 * 60), (0.20, 0.02));
+    }
+}
@@ -0,0 +1,478 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::prelude::*;
+use afbv4::prelude::*;
+use display_lvgl_gui::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::sync::{Mutex, MutexGuard};
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct ThemeColor {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl ThemeColor {
+    fn to_lvgl(self) -> LvglColor {
+        LvglColor::rvb(self.red, self.green, self.blue)
+    }
+
+    fn to_rgb(self) -> (u8, u8, u8) {
+        (self.red, self.green, self.blue)
+    }
+}
+
+// one named theme, 'font' is a FontCatalog uid (default fonts theme)
+#[derive(Deserialize, Debug, Clone)]
+pub struct ThemeProfile {
+    #[serde(default)]
+    pub dark: bool,
+    pub primary: ThemeColor,
+    pub secondary: ThemeColor,
+    #[serde(default)]
+    pub font: Option<String>,
+}
+
+impl Default for ThemeProfile {
+    // lvgl light-blue/blue-grey palette
+    fn default() -> Self {
+        ThemeProfile {
+            dark: false,
+            primary: ThemeColor { red: 0x03, green: 0xA9, blue: 0xF4 },
+            secondary: ThemeColor { red: 0x60, green: 0x7D, blue: 0x8B },
+            font: None,
+        }
+    }
+}
+
+impl ThemeProfile {
+    // black background with yellow/white accents, used by accessibility mode when not configured
+    fn accessible() -> Self {
+        ThemeProfile {
+            dark: true,
+            primary: ThemeColor { red: 0xF0, green: 0xE4, blue: 0x42 },
+            secondary: ThemeColor { red: 0xFF, green: 0xFF, blue: 0xFF },
+            font: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleMode {
+    #[default]
+    Off,
+    Time,
+    Sun,
+}
+
+// 'time' switches at fixed sunrise/sunset, 'sun' computes them from station position
+#[derive(Deserialize, Debug, Clone)]
+pub struct ThemeSchedule {
+    #[serde(default)]
+    pub mode: ScheduleMode,
+    #[serde(default)]
+    pub day: String,
+    #[serde(default)]
+    pub night: String,
+    #[serde(default = "default_sunrise")]
+    pub sunrise: String,
+    #[serde(default = "default_sunset")]
+    pub sunset: String,
+    #[serde(default)]
+    pub latitude: f64,
+    #[serde(default)]
+    pub longitude: f64,
+    #[serde(default = "default_period")]
+    pub period: u32,
+}
+
+fn default_sunrise() -> String {
+    "07:00".to_string()
+}
+
+fn default_sunset() -> String {
+    "20:00".to_string()
+}
+
+fn default_period() -> u32 {
+    60
+}
+
+impl Default for ThemeSchedule {
+    fn default() -> Self {
+        ThemeSchedule {
+            mode: ScheduleMode::Off,
+            day: String::new(),
+            night: String::new(),
+            sunrise: default_sunrise(),
+            sunset: default_sunset(),
+            latitude: 0.0,
+            longitude: 0.0,
+            period: default_period(),
+        }
+    }
+}
+
+// binding config 'themes' object
+#[derive(Deserialize, Debug, Clone)]
+pub struct ThemeConfig {
+    pub default: String,
+    pub profiles: HashMap<String, ThemeProfile>,
+    #[serde(default)]
+    pub schedule: ThemeSchedule,
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        let mut profiles = HashMap::new();
+        profiles.insert("default".to_string(), ThemeProfile::default());
+        ThemeConfig {
+            default: "default".to_string(),
+            profiles,
+            schedule: ThemeSchedule::default(),
+        }
+    }
+}
+
+impl ThemeConfig {
+    pub fn from_jsonc(jthemes: JsoncObj) -> Result<Self, AfbError> {
+        match serde_json::from_str::<ThemeConfig>(jthemes.to_string().as_str()) {
+            Ok(value) => Ok(value),
+            Err(error) => afb_error!("theme-config-fail", "invalid themes config:{}", error),
+        }
+    }
+
+    // legacy single 'theme' object becomes the only profile
+    pub fn from_legacy(jtheme: JsoncObj) -> Result<Self, AfbError> {
+        let profile = match serde_json::from_str::<ThemeProfile>(jtheme.to_string().as_str()) {
+            Ok(value) => value,
+            Err(error) => return afb_error!("theme-config-fail", "invalid theme config:{}", error),
+        };
+        let mut config = ThemeConfig::default();
+        config.profiles.insert(config.default.clone(), profile);
+        Ok(config)
+    }
+}
+
+// 'HH:MM' schedule time, tariff keeps its own error code
+fn parse_schedule(value: &str) -> Result<u32, AfbError> {
+    match parse_minutes(value) {
+        Ok(minutes) => Ok(minutes),
+        Err(_) => afb_error!("theme-schedule-time", "invalid schedule time:'{}' expect HH:MM", value),
+    }
+}
+
+// day of year and local offset to UTC in minutes
+fn local_calendar() -> (u32, i32) {
+    let day = get_time("%j").ok().and_then(|value| value.trim().parse::<u32>().ok()).unwrap_or(1);
+    let offset = match get_time("%z") {
+        Ok(value) if value.len() == 5 => {
+            let hours = value[1..3].parse::<i32>().unwrap_or(0);
+            let minutes = value[3..5].parse::<i32>().unwrap_or(0);
+            let sign = if value.starts_with('-') { -1 } else { 1 };
+            sign * (hours * 60 + minutes)
+        }
+        _ => 0,
+    };
+    (day, offset)
+}
+
+// local minute of day for sunrise and sunset, Err(true) when sun never sets, Err(false) when it never rises
+fn sun_minutes(latitude: f64, longitude: f64, day: u32, offset: i32) -> Result<(u32, u32), bool> {
+    let day = day as f64;
+
+    // NOAA approximation computed at noon, good to a couple of minutes
+    let gamma = 2.0 * PI / 365.0 * (day - 1.0 + 0.5);
+    let eqtime = 229.18
+        * (0.000075 + 0.001868 * gamma.cos()
+            - 0.032077 * gamma.sin()
+            - 0.014615 * (2.0 * gamma).cos()
+            - 0.040849 * (2.0 * gamma).sin());
+    let decl = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin() - 0.006758 * (2.0 * gamma).cos()
+        + 0.000907 * (2.0 * gamma).sin()
+        - 0.002697 * (3.0 * gamma).cos()
+        + 0.00148 * (3.0 * gamma).sin();
+
+    let lat = latitude.to_radians();
+    let cos_ha = 90.833_f64.to_radians().cos() / (lat.cos() * decl.cos()) - lat.tan() * decl.tan();
+    if cos_ha > 1.0 {
+        return Err(false);
+    }
+    if cos_ha < -1.0 {
+        return Err(true);
+    }
+    let ha = cos_ha.acos().to_degrees();
+
+    let local = |utc: f64| (utc.round() as i32 + offset).rem_euclid(1440) as u32;
+    let sunrise = local(720.0 - 4.0 * (longitude + ha) - eqtime);
+    let sunset = local(720.0 - 4.0 * (longitude - ha) - eqtime);
+    Ok((sunrise, sunset))
+}
+
+fn is_daytime(now: u32, sunrise: u32, sunset: u32) -> bool {
+    if sunrise <= sunset {
+        now >= sunrise && now < sunset
+    } else {
+        now >= sunrise || now < sunset
+    }
+}
+
+pub struct ThemeData {
+    active: String,
+    forced: Option<String>,
+    daytime: Option<bool>,
+}
+
+// theme parameters only, switches after startup run on lvgl thread through dispatch
+pub struct ThemeHandle {
+    profiles: HashMap<String, ThemeProfile>,
+    schedule: ThemeSchedule,
+    sunrise: u32,
+    sunset: u32,
+    fonts: &'static FontCatalog,
+    data: Mutex<ThemeData>,
+    dispatch: &'static LvglDispatch,
+}
+
+impl ThemeHandle {
+    pub fn new(
+        mut config: ThemeConfig,
+        fonts: &'static FontCatalog,
+        dispatch: &'static LvglDispatch,
+    ) -> Result<&'static Self, AfbError> {
+        config
+            .profiles
+            .entry("accessible".to_string())
+            .or_insert_with(ThemeProfile::accessible);
+        for (name, profile) in &config.profiles {
+            if let Some(uid) = &profile.font {
+                if fonts.get_font(uid).is_err() {
+                    return afb_error!("theme-config-fail", "theme:{} unknown font:{}", name, uid);
+                }
+            }
+        }
+        let mut names = vec![&config.default];
+        if config.schedule.mode != ScheduleMode::Off {
+            names.push(&config.schedule.day);
+            names.push(&config.schedule.night);
+        }
+        for name in names {
+            if !config.profiles.contains_key(name) {
+                return afb_error!("theme-config-fail", "unknown theme:'{}'", name);
+            }
+        }
+
+        let theme = ThemeHandle {
+            sunrise: parse_schedule(config.schedule.sunrise.as_str())?,
+            sunset: parse_schedule(config.schedule.sunset.as_str())?,
+            profiles: config.profiles,
+            schedule: config.schedule,
+            fonts,
+            data: Mutex::new(ThemeData {
+                active: config.default,
+                forced: None,
+                daytime: None,
+            }),
+            dispatch,
+        };
+
+        // initial theme follows schedule when enabled
+        if let Some(value) = theme.get_daytime() {
+            let mut data = theme.get_state()?;
+            data.daytime = Some(value);
+            data.active = theme.scheduled_name(value).to_string();
+        }
+
+        Ok(Box::leak(Box::new(theme)))
+    }
+
+    // initial theme is applied before panel is drawn and lvgl loop started
+    pub fn init(&self, display: &mut DisplayHandle) -> Result<(), AfbError> {
+        let data = self.get_state()?;
+        let profile = &self.profiles[&data.active];
+        display.set_theme(profile.primary.to_lvgl(), profile.secondary.to_lvgl(), profile.dark, self.get_font(profile));
+        Ok(())
+    }
+
+    pub fn get_state(&self) -> Result<MutexGuard<'_, ThemeData>, AfbError> {
+        match self.data.lock() {
+            Ok(value) => Ok(value),
+            Err(_) => afb_error!("theme-state-lock", "fail to lock theme state"),
+        }
+    }
+
+    pub fn get_period(&self) -> u32 {
+        self.schedule.period.max(1) * 1000
+    }
+
+    pub fn get_active(&self) -> Result<String, AfbError> {
+        Ok(self.get_state()?.active.clone())
+    }
+
+    pub fn get_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.profiles.keys().map(|name| name.as_str()).collect();
+        names.sort();
+        names
+    }
+
+    fn scheduled_name(&self, daytime: bool) -> &str {
+        if daytime {
+            self.schedule.day.as_str()
+        } else {
+            self.schedule.night.as_str()
+        }
+    }
+
+    // None when schedule is off
+    fn get_daytime(&self) -> Option<bool> {
+        let now = local_minutes();
+        match self.schedule.mode {
+            ScheduleMode::Off => None,
+            ScheduleMode::Time => Some(is_daytime(now, self.sunrise, self.sunset)),
+            ScheduleMode::Sun => {
+                let (day, offset) = local_calendar();
+                match sun_minutes(self.schedule.latitude, self.schedule.longitude, day, offset) {
+                    Ok((sunrise, sunset)) => Some(is_daytime(now, sunrise, sunset)),
+                    Err(polar) => Some(polar),
+                }
+            }
+        }
+    }
+
+    fn get_font(&self, profile: &ThemeProfile) -> &'static LvglFont {
+        match &profile.font {
+            Some(uid) => self.fonts.get_font(uid).unwrap_or(self.fonts.get_theme()),
+            None => self.fonts.get_theme(),
+        }
+    }
+
+    pub fn has_theme(&self, name: &str) -> bool {
+        self.profiles.contains_key(name)
+    }
+
+    fn get_profile(&'static self, name: &str) -> Result<&'static ThemeProfile, AfbError> {
+        match self.profiles.get(name) {
+            Some(value) => Ok(value),
+            None => afb_error!("theme-unknown", "unknown theme:'{}'", name),
+        }
+    }
+
+    fn post(&'static self, profile: &'static ThemeProfile) {
+        let font = self.get_font(profile);
+        self.dispatch.post(move || {
+            DisplayHandle::switch_theme(profile.primary.to_rgb(), profile.secondary.to_rgb(), profile.dark, font);
+        });
+    }
+
+    // manual switch holds until next scheduled day/night transition, a forced theme stays on top
+    pub fn set_theme(&'static self, name: &str) -> Result<(), AfbError> {
+        let profile = self.get_profile(name)?;
+        let mut data = self.get_state()?;
+        data.active = name.to_string();
+        if data.forced.is_none() {
+            self.post(profile);
+        }
+        Ok(())
+    }
+
+    // accessibility mode pins its theme, None restores active one
+    pub fn force_theme(&'static self, name: Option<&str>) -> Result<(), AfbError> {
+        let mut data = self.get_state()?;
+        let profile = match name {
+            Some(value) => self.get_profile(value)?,
+            None => self.get_profile(data.active.as_str())?,
+        };
+        data.forced = name.map(|value| value.to_string());
+        self.post(profile);
+        Ok(())
+    }
+
+    // called from schedule timer
+    pub fn tick(&'static self) -> Result<(), AfbError> {
+        let daytime = match self.get_daytime() {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+        let mut data = self.get_state()?;
+        if data.daytime == Some(daytime) {
+            return Ok(());
+        }
+        data.daytime = Some(daytime);
+        let name = self.scheduled_name(daytime);
+        drop(data);
+        afb_log_msg!(Notice, None, "theme schedule switch to:{}", name);
+        self.set_theme(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // minutes between two local times, wrapping over midnight
+    fn diff(a: u32, b: u32) -> i32 {
+        let delta = (a as i32 - b as i32).rem_euclid(1440);
+        delta.min(1440 - delta)
+    }
+
+    #[test]
+    fn sun_paris_solstices() {
+        // Paris, UTC+2 in june, UTC+1 in december
+        let (rise, set) = sun_minutes(48.85, 2.35, 172, 120).unwrap();
+        assert!(diff(rise, 5 * 60 + 47) <= 5, "june sunrise:{}", rise);
+        assert!(diff(set, 21 * 60 + 58) <= 5, "june sunset:{}", set);
+
+        let (rise, set) = sun_minutes(48.85, 2.35, 355, 60).unwrap();
+        assert!(diff(rise, 8 * 60 + 42) <= 5, "december sunrise:{}", rise);
+        assert!(diff(set, 16 * 60 + 55) <= 5, "december sunset:{}", set);
+    }
+
+    #[test]
+    fn sun_equator_twelve_hours() {
+        let (rise, set) = sun_minutes(0.0, 0.0, 80, 0).unwrap();
+        let length = set - rise;
+        assert!((12 * 60 - 10..=12 * 60 + 15).contains(&length), "equinox day length:{}", length);
+        assert!(diff(rise, 6 * 60) <= 15, "equinox sunrise:{}", rise);
+    }
+
+    #[test]
+    fn sun_polar_day_and_night() {
+        assert_eq!(sun_minutes(78.2, 15.6, 172, 120), Err(true));
+        assert_eq!(sun_minutes(78.2, 15.6, 355, 60), Err(false));
+    }
+
+    #[test]
+    fn daytime_window() {
+        assert!(is_daytime(12 * 60, 7 * 60, 20 * 60));
+        assert!(is_daytime(7 * 60, 7 * 60, 20 * 60));
+        assert!(!is_daytime(20 * 60, 7 * 60, 20 * 60));
+        assert!(!is_daytime(3 * 60, 7 * 60, 20 * 60));
+    }
+
+    #[test]
+    fn daytime_over_midnight() {
+        // sunset after midnight local time (far west of timezone meridian)
+        assert!(is_daytime(23 * 60, 8 * 60, 30));
+        assert!(is_daytime(10, 8 * 60, 30));
+        assert!(!is_daytime(60, 8 * 60, 30));
+    }
+}
@@ -16,10 +16,11 @@ use display_lvgl_gui::prelude::*;
 use typesv4::prelude::*;
 
 use std::cell::Cell;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 macro_rules! handler_by_uid {
-    ($api: ident, $display:ident, $uid:literal, $apievt:ident, $pattern:literal, $widget:ty, $ctx_type: ident, $callb: ident) => {
+    ($api: ident, $display:ident, $uid:literal, $apievt:ident, $pattern:literal, $widget:ty, $ctx_type: ident, $callb: ident $(, $field:ident $(: $value:expr)?)*) => {
         let widget = match $display.get_by_uid($uid).downcast_ref::<$widget>() {
             Some(widget) => widget,
             None => {
@@ -35,19 +36,71 @@ macro_rules! handler_by_uid {
             .set_info(widget.get_info())
             .set_pattern(to_static_str(format!("{}/{}", $apievt, $pattern)))
             .set_callback($callb)
-            .set_context($ctx_type { widget })
+            .set_context($ctx_type { widget $(, $field $(: $value)?)* })
             .finalize()?;
 
         $api.add_evt_handler(handler);
     };
 }
 
+// typed widget lookup within display panel
+pub(crate) fn get_widget<T: 'static>(display: &DisplayHandle, uid: &'static str) -> Result<&'static T, AfbError> {
+    match display.get_by_uid(uid).downcast_ref::<T>() {
+        Some(widget) => Ok(widget),
+        None => afb_error!(
+            "verb-info-widget",
+            "no widget uid:{} type:{} found in panel",
+            uid,
+            std::any::type_name::<T>()
+        ),
+    }
+}
+
+// raw panel widget lookup, used to register press gestures
+pub(crate) fn get_panel_widget(display: &DisplayHandle, uid: &str) -> Result<&'static LvglWidget, AfbError> {
+    match display.get_panel().iter().find(|widget| widget.get_uid() == uid) {
+        Some(widget) => Ok(*widget),
+        None => afb_error!("verb-info-widget", "no widget uid:{} found in panel", uid),
+    }
+}
+
 struct WidgetEvtCtx {
     event: &'static AfbEvent,
 }
 
-struct ASyncCallData {
-    my_counter: u32,
+fn bind_press(widget: &LvglWidget, press: &'static PressEvtCtx) -> Result<(), AfbError> {
+    match widget.on_press(press) {
+        Ok(()) => Ok(()),
+        Err(error) => afb_error!("verb-press-fail", "{}", error),
+    }
+}
+
+struct PressEvtCtx {
+    locale: &'static LocaleHandle,
+    access: &'static AccessHandle,
+    carousel: &'static CarouselHandle,
+}
+
+impl GuiPressHandler for PressEvtCtx {
+    fn pressed(&self, uid: &'static str, press: GuiPress) {
+        // any press is user activity, a shown slide is removed at once
+        if press == GuiPress::Down {
+            if let Err(error) = self.carousel.interrupt() {
+                afb_log_msg!(Warning, None, "fail to interrupt carousel:{}", error);
+            }
+        }
+        // translate icon cycles over configured languages
+        if uid == "Pixmap-lang" && press == GuiPress::Click {
+            if let Err(error) = self.locale.cycle() {
+                afb_log_msg!(Warning, None, "fail to cycle locale:{}", error);
+            }
+        }
+        if press == GuiPress::Long && self.access.is_trigger(uid) {
+            if let Err(error) = self.access.toggle() {
+                afb_log_msg!(Warning, None, "fail to toggle accessibility:{}", error);
+            }
+        }
+    }
 }
 
 impl LvglHandler for WidgetEvtCtx {
@@ -90,117 +143,343 @@ fn subscribe_evt_cb(
 
 struct TextCtx {
     widget: &'static LvglTextArea,
+    dispatch: &'static LvglDispatch,
 }
 
 fn info_verb_cb(rqt: &AfbRequest, args: &AfbRqtData, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
     let ctx = ctx_data.get_ref::<TextCtx>()?;
     let text = args.get::<String>(0)?;
-    ctx.widget.set_value(text.as_str());
+    let widget = ctx.widget;
+    ctx.dispatch.post(move || {
+        widget.set_value(text.as_str());
+    });
     rqt.reply(AFB_NO_DATA, 0);
     Ok(())
 }
 
 struct MeterCtx {
     widget: &'static LvglMeter,
+    dispatch: &'static LvglDispatch,
 }
 fn meter_verb_cb(rqt: &AfbRequest, args: &AfbRqtData, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
     let ctx = ctx_data.get_ref::<MeterCtx>()?;
     let value = args.get::<i32>(0)?;
-    ctx.widget.set_value(value);
+    let widget = ctx.widget;
+    ctx.dispatch.post(move || {
+        widget.set_value(value);
+    });
     rqt.reply(AFB_NO_DATA, 0);
     Ok(())
 }
 
 struct ArcCtx {
     widget: &'static LvglArc,
+    dispatch: &'static LvglDispatch,
 }
 
 fn arc_verb_cb(rqt: &AfbRequest, args: &AfbRqtData, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
     let ctx = ctx_data.get_ref::<ArcCtx>()?;
     let value = args.get::<i32>(0)?;
-    ctx.widget.set_value(value);
+    let widget = ctx.widget;
+    ctx.dispatch.post(move || {
+        widget.set_value(value);
+    });
     rqt.reply(AFB_NO_DATA, 0);
     Ok(())
 }
 
 struct BarCtx {
     widget: &'static LvglBar,
+    dispatch: &'static LvglDispatch,
 }
 
 fn bar_verb_cb(rqt: &AfbRequest, args: &AfbRqtData, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
     let ctx = ctx_data.get_ref::<BarCtx>()?;
     let value = args.get::<i32>(0)?;
-    ctx.widget.set_value(value);
+    let widget = ctx.widget;
+    ctx.dispatch.post(move || {
+        widget.set_value(value);
+    });
     rqt.reply(AFB_NO_DATA, 0);
     Ok(())
 }
 
 struct NfcCtx {
     widget: &'static LvglPixButton,
+    dispatch: &'static LvglDispatch,
 }
 
 fn ncf_verb_cb(rqt: &AfbRequest, args: &AfbRqtData, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
     let ctx = ctx_data.get_ref::<NfcCtx>()?;
-    match args.get::<&QueryOnOff>(0)? {
-        QueryOnOff::ON => {
-            ctx.widget.set_value(AssetPixmap::nfc_on());
-        }
-        QueryOnOff::OFF => {
-            ctx.widget.set_value(AssetPixmap::ethernet_on());
-        }
-    }
+    let pixmap = match args.get::<&QueryOnOff>(0)? {
+        QueryOnOff::ON => AssetPixmap::nfc_on(),
+        QueryOnOff::OFF => AssetPixmap::nfc_off(),
+    };
+    let widget = ctx.widget;
+    ctx.dispatch.post(move || {
+        widget.set_value(pixmap);
+    });
     rqt.reply(AFB_NO_DATA, 0);
     Ok(())
 }
 
 struct SwitchCtx {
     widget: &'static LvglSwitch,
+    dispatch: &'static LvglDispatch,
 }
 
 fn switch_verb_cb(rqt: &AfbRequest, args: &AfbRqtData, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
     let ctx = ctx_data.get_ref::<SwitchCtx>()?;
-    match args.get::<&QueryOnOff>(0)? {
-        QueryOnOff::ON => {
-            ctx.widget.set_value(true);
-        }
-        QueryOnOff::OFF => {
-            ctx.widget.set_value(false);
-        }
-    }
+    let value = match args.get::<&QueryOnOff>(0)? {
+        QueryOnOff::ON => true,
+        QueryOnOff::OFF => false,
+    };
+    let widget = ctx.widget;
+    ctx.dispatch.post(move || {
+        widget.set_value(value);
+    });
     rqt.reply(AFB_NO_DATA, 0);
     Ok(())
 }
 
 struct LedCtx {
     widget: &'static LvglLed,
+    dispatch: &'static LvglDispatch,
 }
 
-fn led_verb_cb(rqt: &AfbRequest, args: &AfbRqtData, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
-    let ctx = ctx_data.get_ref::<LedCtx>()?;
+struct AccessVerbCtx {
+    access: &'static AccessHandle,
+}
+
+fn access_verb_cb(rqt: &AfbRequest, args: &AfbRqtData, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx_data.get_ref::<AccessVerbCtx>()?;
+    let value = match args.get::<&QueryOnOff>(0)? {
+        QueryOnOff::ON => true,
+        QueryOnOff::OFF => false,
+    };
+    ctx.access.set_enabled(value)?;
+    rqt.reply(AFB_NO_DATA, 0);
+    Ok(())
+}
+
+struct BacklightCtx {
+    backlight: &'static BacklightHandle,
+}
+
+fn backlight_verb_cb(rqt: &AfbRequest, args: &AfbRqtData, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx_data.get_ref::<BacklightCtx>()?;
     match args.get::<&QueryOnOff>(0)? {
-        QueryOnOff::ON => {
-            ctx.widget.set_on(true);
-        }
-        QueryOnOff::OFF => {
-            ctx.widget.set_on(false);
-        }
+        QueryOnOff::ON => ctx.backlight.wake()?,
+        QueryOnOff::OFF => ctx.backlight.sleep()?,
     }
     rqt.reply(AFB_NO_DATA, 0);
     Ok(())
 }
 
+// dimming/screensaver delays and touch wake-up
+fn backlight_timer_cb(_timer: &AfbTimer, _decount: u32, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx_data.get_ref::<BacklightCtx>()?;
+    ctx.backlight.tick()
+}
+
+struct CarouselTimerCtx {
+    carousel: &'static CarouselHandle,
+}
+
+// slide rotation, idle restart and touch outside of carousel
+fn carousel_timer_cb(_timer: &AfbTimer, _decount: u32, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx_data.get_ref::<CarouselTimerCtx>()?;
+    ctx.carousel.tick()
+}
+
+fn led_verb_cb(rqt: &AfbRequest, args: &AfbRqtData, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx_data.get_ref::<LedCtx>()?;
+    let value = match args.get::<&QueryOnOff>(0)? {
+        QueryOnOff::ON => true,
+        QueryOnOff::OFF => false,
+    };
+    let widget = ctx.widget;
+    ctx.dispatch.post(move || {
+        widget.set_on(value);
+    });
+    rqt.reply(AFB_NO_DATA, 0);
+    Ok(())
+}
+
 struct TimerCtx {
     time: &'static LvglLabel,
     date: &'static LvglLabel,
+    dispatch: &'static LvglDispatch,
 }
 // Callback is called for each tick until decount>0
 fn timer_callback(_timer: &AfbTimer, _decount: u32, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
     let ctx = ctx_data.get_ref::<TimerCtx>()?;
-    ctx.time.set_value(get_time("%H:%M").unwrap().as_str());
-    ctx.date.set_value(get_time("%D").unwrap().as_str());
+    let (time, date) = (ctx.time, ctx.date);
+    let time_value = get_time("%H:%M").unwrap();
+    let date_value = get_time("%D").unwrap();
+    ctx.dispatch.post(move || {
+        time.set_value(time_value.as_str());
+        date.set_value(date_value.as_str());
+    });
+    Ok(())
+}
+
+struct RefreshTimerCtx {
+    refresh: &'static LvglRefresh,
+    dispatch: &'static LvglDispatch,
+}
+
+fn refresh_timer_cb(_timer: &AfbTimer, _decount: u32, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx_data.get_ref::<RefreshTimerCtx>()?;
+    let refresh = ctx.refresh;
+    ctx.dispatch.post(move || {
+        refresh.flush();
+    });
+    Ok(())
+}
+
+struct PanelTimerCtx {
+    reservation: &'static ReservationPanel,
+    limit: &'static LimitPanel,
+    watchdog: &'static StaleWatchdog,
+}
+
+// reservation and power limit countdowns, stale value watchdog
+fn panel_timer_cb(_timer: &AfbTimer, _decount: u32, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx_data.get_ref::<PanelTimerCtx>()?;
+    ctx.reservation.tick()?;
+    ctx.limit.tick()?;
+    ctx.watchdog.tick()?;
+    Ok(())
+}
+
+struct NetworkTimerCtx {
+    network: &'static NetworkPanel,
+}
+
+fn network_timer_cb(_timer: &AfbTimer, _decount: u32, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx_data.get_ref::<NetworkTimerCtx>()?;
+    ctx.network.poll()?;
+    Ok(())
+}
+
+struct SessionTimerCtx {
+    widget: &'static LvglLabel,
+    cost: &'static LvglLabel,
+    session: &'static SessionHandle,
+    summary: &'static SummaryScreen,
+    refresh: &'static LvglRefresh,
+}
+
+// elapsed charging time and running cost refreshed every second, unchanged values are skipped by refresh
+fn session_timer_cb(_timer: &AfbTimer, _decount: u32, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx_data.get_ref::<SessionTimerCtx>()?;
+    let elapsed = ctx.session.get_elapsed()?;
+    ctx.refresh.set_label(ctx.widget, fmt_duration(elapsed));
+
+    ctx.session.update_cost()?;
+    if let (Some(tariff), Some(cost)) = (ctx.session.get_tariff(), ctx.session.get_cost()?) {
+        ctx.refresh.set_label(ctx.cost, tariff.fmt_cost(cost));
+    }
+
+    // back to home screen once summary timed out after plug-out
+    if ctx.session.check_summary_timeout()? {
+        ctx.summary.hide();
+    }
+    Ok(())
+}
+
+struct LocaleVerbCtx {
+    locale: &'static LocaleHandle,
+}
+
+fn locale_verb_cb(rqt: &AfbRequest, args: &AfbRqtData, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx_data.get_ref::<LocaleVerbCtx>()?;
+    let language = args.get::<String>(0)?;
+    ctx.locale.set_locale(language.as_str())?;
+    rqt.reply(AFB_NO_DATA, 0);
+    Ok(())
+}
+
+struct QrcodeVerbCtx {
+    qrcode: &'static QrcodePanel,
+}
+
+// replace qrcode payload template, tags are filled from live state
+fn qrcode_verb_cb(rqt: &AfbRequest, args: &AfbRqtData, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx_data.get_ref::<QrcodeVerbCtx>()?;
+    let template = args.get::<String>(0)?;
+    ctx.qrcode.set_template(template.as_str())?;
+    rqt.reply(AFB_NO_DATA, 0);
+    Ok(())
+}
+
+struct AssetVerbCtx {
+    pixmaps: HashMap<&'static str, &'static LvglPixmap>,
+    dispatch: &'static LvglDispatch,
+}
+
+// list asset names or set any panel pixmap to a named asset
+fn asset_verb_cb(rqt: &AfbRequest, args: &AfbRqtData, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx_data.get_ref::<AssetVerbCtx>()?;
+    match args.get::<&QueryAsset>(0)? {
+        QueryAsset::LIST => {
+            let names = match serde_json::to_string(&AssetPixmap::list()) {
+                Ok(value) => value,
+                Err(error) => return afb_error!("asset-list-fail", "fail to serialize asset list:{}", error),
+            };
+            rqt.reply(JsoncObj::import(names.as_str())?, 0);
+        }
+        QueryAsset::SET { uid, asset } => {
+            let widget = match ctx.pixmaps.get(uid.as_str()) {
+                Some(widget) => *widget,
+                None => return afb_error!("asset-widget-unknown", "no pixmap uid:{}", uid),
+            };
+            let pixmap = match AssetPixmap::by_name(asset.as_str()) {
+                Some(pixmap) => pixmap,
+                None => return afb_error!("asset-name-unknown", "no asset name:{}", asset),
+            };
+            ctx.dispatch.post(move || {
+                widget.set_value(pixmap);
+            });
+            rqt.reply(AFB_NO_DATA, 0);
+        }
+    }
     Ok(())
 }
 
+struct ThemeVerbCtx {
+    theme: &'static ThemeHandle,
+}
+
+// list named themes or force one until next scheduled day/night switch
+fn theme_verb_cb(rqt: &AfbRequest, args: &AfbRqtData, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx_data.get_ref::<ThemeVerbCtx>()?;
+    match args.get::<&QueryTheme>(0)? {
+        QueryTheme::LIST => {
+            let themes = serde_json::json!({
+                "active": ctx.theme.get_active()?,
+                "themes": ctx.theme.get_names(),
+            });
+            rqt.reply(JsoncObj::import(themes.to_string().as_str())?, 0);
+        }
+        QueryTheme::SET { name } => {
+            ctx.theme.set_theme(name.as_str())?;
+            rqt.reply(AFB_NO_DATA, 0);
+        }
+    }
+    Ok(())
+}
+
+struct ThemeTimerCtx {
+    theme: &'static ThemeHandle,
+}
+
+fn theme_timer_cb(_timer: &AfbTimer, _decount: u32, ctx_data: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx_data.get_ref::<ThemeTimerCtx>()?;
+    ctx.theme.tick()
+}
+
 //------------------------------------------------------------------
 
 struct UserCtxData {
@@ -256,20 +535,63 @@ fn event_get_callback(
 
 struct MgrEvtEngyCtrl {
     widget: &'static LvglLabel,
+    refresh: &'static LvglRefresh,
+    stale: &'static StaleSource,
+}
+
+struct MgrEvtEnergyCtrl {
+    widget: &'static LvglLabel,
+    session: &'static SessionHandle,
+    refresh: &'static LvglRefresh,
+    stale: &'static StaleSource,
+}
+
+struct MgrEvtGridCtrl {
+    grid: &'static GridPanel,
+}
+
+struct MgrEvtCurveCtrl {
+    widget: &'static LvglLabel,
+    session: &'static SessionHandle,
+    chart: &'static LvglChart,
+    refresh: &'static LvglRefresh,
+    stale: &'static StaleSource,
 }
 
 struct MgrEvtChmgrCtrl {
-    widget_charge: &'static LvglPixmap,
-    widget_plug_status: &'static LvglPixmap,
     widget_iec_status: &'static LvglSwitch,
+    summary: &'static SummaryScreen,
+    fault: &'static FaultScreen,
+    reservation: &'static ReservationPanel,
+    limit: &'static LimitPanel,
+    auth: &'static AuthPanel,
+    session: &'static SessionHandle,
+    qrcode: &'static QrcodePanel,
+    chart: &'static LvglChart,
+    icons: &'static StatusIcons,
+    backlight: &'static BacklightHandle,
+    carousel: &'static CarouselHandle,
+    engy_api: &'static str,
+    stale: &'static StaleSource,
+    dispatch: &'static LvglDispatch,
 }
 
 struct MgrEvtNfcCtrl {
     widget_nfc_status: &'static LvglPixmap,
+    backlight: &'static BacklightHandle,
+    carousel: &'static CarouselHandle,
+    dispatch: &'static LvglDispatch,
 }
 
 struct MgrEvtAuthCrl {
-    widget: &'static LvglPixmap,
+    session: &'static SessionHandle,
+    auth: &'static AuthPanel,
+    icons: &'static StatusIcons,
+    backlight: &'static BacklightHandle,
+    carousel: &'static CarouselHandle,
+    auth_api: &'static str,
+    stale: &'static StaleSource,
+    dispatch: &'static LvglDispatch,
 }
 
 
@@ -282,7 +604,106 @@ fn evt_nrj_cb(
 ) -> Result<(), AfbError> {
         let ctx = ctx_data.get_ref::<MgrEvtEngyCtrl>()?;
         let data = args.get::<&MeterDataSet>(0)?;
-        ctx.widget.set_value(format!("{:.2}", (data.total as f64)/1000.0).as_str());
+        ctx.stale.touch()?;
+        ctx.refresh.set_label(ctx.widget, format!("{:.2}", (data.total as f64)/1000.0));
+        Ok(())
+}
+
+// widget updates from binder threads are executed from lvgl thread
+fn post_pixmap(dispatch: &LvglDispatch, widget: &'static LvglPixmap, pixmap: &'static LvglImgDsc) {
+    dispatch.post(move || {
+        widget.set_value(pixmap);
+    });
+}
+
+fn post_switch(dispatch: &LvglDispatch, widget: &'static LvglSwitch, value: bool) {
+    dispatch.post(move || {
+        widget.set_value(value);
+    });
+}
+
+// chart reload on next refresh flush, curve is read at drawing time
+fn chart_coalesce(refresh: &LvglRefresh, chart: &'static LvglChart, session: &'static SessionHandle) {
+    refresh.set_redraw(
+        chart.get_uid(),
+        Box::new(move || {
+            if let Ok((power, current)) = session.get_curve() {
+                chart_draw(chart, &power, &current);
+            }
+        }),
+    );
+}
+
+// lvgl thread only
+fn chart_draw(chart: &LvglChart, power: &[i16], current: &[i16]) {
+    if power.is_empty() {
+        chart.set_count(1).set_values(0, &[0]).set_values(1, &[0]);
+    } else {
+        chart
+            .set_count(power.len())
+            .set_values(0, power)
+            .set_values(1, current);
+    }
+}
+
+// reload the whole chart from session rolling window
+fn chart_refresh(dispatch: &LvglDispatch, chart: &'static LvglChart, session: &SessionHandle) -> Result<(), AfbError> {
+    let (power, current) = session.get_curve()?;
+    dispatch.post(move || chart_draw(chart, &power, &current));
+    Ok(())
+}
+
+fn evt_power_cb(
+    _event: &AfbEventMsg,
+    args: &AfbRqtData,
+    ctx_data: &AfbCtxData
+) -> Result<(), AfbError> {
+        let ctx = ctx_data.get_ref::<MgrEvtCurveCtrl>()?;
+        let data = args.get::<&MeterDataSet>(0)?;
+        ctx.stale.touch()?;
+        ctx.refresh.set_label(ctx.widget, format!("{:.2}", (data.total as f64)/1000.0));
+        if ctx.session.push_power(data.total)? {
+            chart_coalesce(ctx.refresh, ctx.chart, ctx.session);
+        }
+        Ok(())
+}
+
+fn evt_energy_cb(
+    _event: &AfbEventMsg,
+    args: &AfbRqtData,
+    ctx_data: &AfbCtxData
+) -> Result<(), AfbError> {
+        let ctx = ctx_data.get_ref::<MgrEvtEnergyCtrl>()?;
+        let data = args.get::<&MeterDataSet>(0)?;
+        ctx.stale.touch()?;
+        ctx.refresh.set_label(ctx.widget, format!("{:.2}", (data.total as f64)/1000.0));
+        ctx.session.set_energy(data.total)?;
+        Ok(())
+}
+
+// available current and over-current from grid meter
+fn evt_grid_cb(
+    event: &AfbEventMsg,
+    args: &AfbRqtData,
+    ctx_data: &AfbCtxData
+) -> Result<(), AfbError> {
+        let ctx = ctx_data.get_ref::<MgrEvtGridCtrl>()?;
+        let data = args.get::<&MeterDataSet>(0)?;
+        afb_log_msg!(Debug, event, "-- evt_grid_cb tag:{:?} total:{}", data.tag, data.total);
+        ctx.grid.set_meter(data);
+        Ok(())
+}
+
+fn evt_current_cb(
+    _event: &AfbEventMsg,
+    args: &AfbRqtData,
+    ctx_data: &AfbCtxData
+) -> Result<(), AfbError> {
+        let ctx = ctx_data.get_ref::<MgrEvtCurveCtrl>()?;
+        let data = args.get::<&MeterDataSet>(0)?;
+        ctx.stale.touch()?;
+        ctx.refresh.set_label(ctx.widget, format!("{:.2}", (data.total as f64)/1000.0));
+        ctx.session.set_current(data.total)?;
         Ok(())
 }
 
@@ -292,46 +713,107 @@ fn evt_chmgr_cb(
     ctx_data: &AfbCtxData,
 ) -> Result<(), AfbError> {
         let ctx = ctx_data.get_ref::<MgrEvtChmgrCtrl>()?;
-        let data = args.get::<&ChargingMsg>(0)?;
+        ctx.stale.touch()?;
+        let data = match args.get::<&ChargingMsg>(0) {
+            Ok(data) => data,
+            Err(error) => {
+                // reservation detail comes as a full session
+                if let Ok(reservation) = args.get::<&ReservationSession>(0) {
+                    afb_log_msg!(Notice, event, "-- evt_chmgr_cb reservation:{:?}", reservation);
+                    ctx.reservation.set_session(reservation)?;
+                    return Ok(());
+                }
+                // smart charging limit from grid or OCPP
+                if let Ok(limit) = args.get::<&PowerLimit>(0) {
+                    afb_log_msg!(Notice, event, "-- evt_chmgr_cb limit:{:?}", limit);
+                    ctx.limit.set_limit(limit)?;
+                    ctx.session.set_transaction(limit.tid)?;
+                    ctx.qrcode.refresh()?;
+                    return Ok(());
+                }
+                // charging manager also pushes raw error states
+                match args.get::<&ErrorState>(0) {
+                    Ok(fault) => {
+                        afb_log_msg!(Warning, event, "-- evt_chmgr_cb fault:{:?}", fault);
+                        let info = fault_info(fault);
+                        if info.permanent {
+                            ctx.icons.set_power("fault");
+                        } else {
+                            ctx.icons.set_plug(&PlugState::Error);
+                        }
+                        ctx.fault.show(&info);
+                        return Ok(());
+                    }
+                    Err(_) => return Err(error),
+                }
+            }
+        };
         afb_log_msg!(Notice, event, "-- evt_chmgr_cb event:{:?}.",data);
         match data {
             ChargingMsg::Power(pdata) => {
                 match pdata {
                     PowerRequest::Start => {
-                        ctx.widget_charge.set_value(AssetPixmap::station_reserved());
+                        ctx.icons.set_power("start");
+                        ctx.summary.hide();
+                        ctx.reservation.clear()?;
+                        ctx.session.start()?;
+                        ctx.qrcode.refresh()?;
+                        chart_refresh(ctx.dispatch, ctx.chart, ctx.session)?;
+                        // session id comes from energy manager state
+                        AfbSubCall::call_async(
+                            event.get_api(),
+                            ctx.engy_api,
+                            "state",
+                            "{'action':'read'}",
+                            async_engy_cb,
+                            AsyncEngyData { session: ctx.session, qrcode: ctx.qrcode },
+                        )?;
                     }
-                    PowerRequest::Charging(_value) => {
-                        ctx.widget_charge.set_value(AssetPixmap::station_charging());
+                    PowerRequest::Charging(value) => {
+                        ctx.icons.set_power("charging");
+                        ctx.session.charging(*value > 0)?;
                     }
                     PowerRequest::Stop(_value) => {
-                        ctx.widget_charge.set_value(AssetPixmap::station_completed());
+                        ctx.icons.set_power("stop");
+                        ctx.session.stop()?;
+                        ctx.session.update_cost()?;
+                        ctx.summary.show(ctx.session)?;
+                        ctx.qrcode.refresh()?;
                     }
                     PowerRequest::Idle => {
-                        ctx.widget_charge.set_value(AssetPixmap::station_available());
+                        ctx.fault.reset();
+                        ctx.icons.set_power("idle");
                     }
                 }
             }
             ChargingMsg::Plugged(sdata) => {
+                ctx.icons.set_plug(sdata);
                 match sdata {
                     PlugState::PlugIn => {
-                        ctx.widget_plug_status.set_value(AssetPixmap::plug_connected_unlocked());
+                        ctx.backlight.wake()?;
+                        ctx.carousel.set_busy(true)?;
+                        ctx.summary.hide();
+                        ctx.session.plug_in()?;
+                        ctx.qrcode.refresh()?;
                     }
                     PlugState::Lock => {
-                        ctx.widget_charge.set_value(AssetPixmap::station_pending_autho());
-                        ctx.widget_plug_status.set_value(AssetPixmap::plug_connected_locked());
+                        ctx.icons.set_power("pending");
                     }
                     PlugState::Error => {
-                        ctx.widget_charge.set_value(AssetPixmap::station_out_of_order());
-                        ctx.widget_charge.set_value(AssetPixmap::plug_error());
+                        ctx.icons.set_power("fault");
+                        ctx.fault.show(&fault_plug_info());
                     }
                     PlugState::PlugOut => {
-                        ctx.widget_charge.set_value(AssetPixmap::station_available());
-                        ctx.widget_plug_status.set_value(AssetPixmap::plug_disconnected());
-                        ctx.widget_iec_status.set_value(false);
-                    }
-                    PlugState::Unknown => {
-                        ctx.widget_plug_status.set_value(AssetPixmap::plug_unknow());
+                        ctx.carousel.set_busy(false)?;
+                        ctx.fault.clear();
+                        ctx.auth.clear();
+                        if !ctx.fault.is_permanent() {
+                            ctx.icons.set_power("idle");
+                        }
+                        post_switch(ctx.dispatch, ctx.widget_iec_status, false);
+                        ctx.session.arm_summary_timeout()?;
                     }
+                    PlugState::Unknown => {}
                 }
             }
             ChargingMsg::Iso(idata) => {
@@ -343,12 +825,18 @@ fn evt_chmgr_cb(
                     IsoState::Iso3 => {
                     }
                     IsoState::Iec => {
-                        ctx.widget_iec_status.set_value(true);
+                        post_switch(ctx.dispatch, ctx.widget_iec_status, true);
                     }
                     IsoState::Unset => {
                     }
                 }
             }
+            ChargingMsg::Reservation(rdata) => {
+                ctx.reservation.set_status(rdata)?;
+            }
+            ChargingMsg::State(state) => {
+                ctx.limit.set_evse_imax(state.imax)?;
+            }
             _ => {
             }
         }
@@ -356,12 +844,38 @@ fn evt_chmgr_cb(
 }
 
 fn evt_nfc_cb(
-    _event: &AfbEventMsg,
-    _args: &AfbRqtData,
+    event: &AfbEventMsg,
+    args: &AfbRqtData,
     ctx_data: &AfbCtxData,
 ) -> Result<(), AfbError> {
         let ctx = ctx_data.get_ref::<MgrEvtNfcCtrl>()?;
-        ctx.widget_nfc_status.set_value(AssetPixmap::nfc_on());
+        let data = match args.get::<&NfcReaderMsg>(0) {
+            Ok(value) => value,
+            // unknown payload is not a card read, reader state is unknown
+            Err(_) => {
+                afb_log_msg!(Debug, event, "-- evt_nfc_cb unknown event ignored");
+                post_pixmap(ctx.dispatch, ctx.widget_nfc_status, AssetPixmap::nfc_off());
+                return Ok(());
+            }
+        };
+        afb_log_msg!(Debug, event, "-- evt_nfc_cb msg:{:?}", data);
+        match data {
+            NfcReaderMsg::Absent => {
+                post_pixmap(ctx.dispatch, ctx.widget_nfc_status, AssetPixmap::nfc_off());
+            }
+            NfcReaderMsg::Error => {
+                post_pixmap(ctx.dispatch, ctx.widget_nfc_status, AssetPixmap::nfc_fail());
+            }
+            NfcReaderMsg::Detected => {
+                ctx.backlight.wake()?;
+                ctx.carousel.interrupt()?;
+                post_pixmap(ctx.dispatch, ctx.widget_nfc_status, AssetPixmap::nfc_on());
+            }
+            // card removal means reader still alive
+            NfcReaderMsg::Present | NfcReaderMsg::Removed => {
+                post_pixmap(ctx.dispatch, ctx.widget_nfc_status, AssetPixmap::nfc_on());
+            }
+        }
         Ok(())
 }
 
@@ -371,28 +885,55 @@ fn evt_auth_cb(
     ctx_data: &AfbCtxData
 ) -> Result<(), AfbError> {
         let ctx = ctx_data.get_ref::<MgrEvtAuthCrl>()?;
+        ctx.stale.touch()?;
         afb_log_msg!(Notice, event, "-- evt_auth_cb event");
         let data = args.get::<&AuthMsg>(0)?;
+        ctx.backlight.wake()?;
+        ctx.carousel.interrupt()?;
+        ctx.icons.set_auth(data);
         match data {
             AuthMsg::Done => {
-                ctx.widget.set_value(AssetPixmap::nfc_done());
-            }
-            AuthMsg::Fail => {
-                ctx.widget.set_value(AssetPixmap::nfc_fail());
-            }
-            AuthMsg::Pending => {
-                ctx.widget.set_value(AssetPixmap::nfc_pending());
+                // auth event does not carry the badge, read it from auth state
+                AfbSubCall::call_async(
+                    event.get_api(),
+                    ctx.auth_api,
+                    "state",
+                    "{'action':'read'}",
+                    async_tagid_cb,
+                    AsyncTagData { session: ctx.session, auth: ctx.auth },
+                )?;
             }
-            AuthMsg::Idle => {
-                ctx.widget.set_value(AssetPixmap::nfc_idle());
+            AuthMsg::Fail | AuthMsg::Idle => {
+                ctx.auth.clear();
             }
+            AuthMsg::Pending => {}
         };
 
         Ok(())
 }
 
+struct AsyncTagData {
+    session: &'static SessionHandle,
+    auth: &'static AuthPanel,
+}
+
+fn async_tagid_cb(
+    api: &AfbApi,
+    args: &AfbRqtData,
+    ctx_data: &AfbCtxData,
+) -> Result<(), AfbError> {
+        let ctx = ctx_data.get_ref::<AsyncTagData>()?;
+        afb_log_msg!(Notice, api, "-- async_tagid_cb");
+        let data = args.get::<&AuthState>(0)?;
+        ctx.session.set_tagid(data.tagid.as_str())?;
+        ctx.auth.set_state(data);
+        Ok(())
+}
+
 struct AsyncAuthData {
-    widget: &'static LvglPixmap,
+    icons: &'static StatusIcons,
+    session: &'static SessionHandle,
+    auth: &'static AuthPanel,
 }
 
 fn async_auth_cb(
@@ -403,31 +944,86 @@ fn async_auth_cb(
         let authdata = ctx_data.get_ref::<AsyncAuthData>()?;
         afb_log_msg!(Notice, api, "-- async_auth_cb");
         let data = args.get::<&AuthState>(0)?;
-        match data.auth {
-            AuthMsg::Done => {
-                authdata.widget.set_value(AssetPixmap::nfc_done());
-            }
-            AuthMsg::Fail => {
-                authdata.widget.set_value(AssetPixmap::nfc_fail());
-            }
-            AuthMsg::Pending => {
-                authdata.widget.set_value(AssetPixmap::nfc_pending());
-            }
-            AuthMsg::Idle => {
-                authdata.widget.set_value(AssetPixmap::nfc_idle());
-            }
-        };
+        authdata.icons.set_auth(&data.auth);
+        if let AuthMsg::Done = data.auth {
+            authdata.session.set_tagid(data.tagid.as_str())?;
+        }
+        authdata.auth.set_state(data);
 
         Ok(())
 }
 
+struct AsyncEngyData {
+    session: &'static SessionHandle,
+    qrcode: &'static QrcodePanel,
+}
+
+fn async_engy_cb(
+    api: &AfbApi,
+    args: &AfbRqtData,
+    ctx_data: &AfbCtxData,
+) -> Result<(), AfbError> {
+        let ctx = ctx_data.get_ref::<AsyncEngyData>()?;
+        let data = args.get::<&EnergyState>(0)?;
+        afb_log_msg!(Notice, api, "-- async_engy_cb session:{} start:{:?}", data.session, data.timestamp);
+        ctx.session.restore(data.timestamp)?;
+        ctx.session.set_engy_session(data.session)?;
+        ctx.qrcode.refresh()?;
+        Ok(())
+}
+
+struct AsyncChmgrData {
+    session: &'static SessionHandle,
+    qrcode: &'static QrcodePanel,
+    limit: &'static LimitPanel,
+    engy_api: &'static str,
+}
+
+// display restarted while a session is active, restore session timer from energy manager
+fn async_chmgr_cb(
+    api: &AfbApi,
+    args: &AfbRqtData,
+    ctx_data: &AfbCtxData,
+) -> Result<(), AfbError> {
+        let ctx = ctx_data.get_ref::<AsyncChmgrData>()?;
+        let data = args.get::<&ChargingState>(0)?;
+        afb_log_msg!(Notice, api, "-- async_chmgr_cb power:{:?}", data.power);
+        ctx.limit.set_evse_imax(data.imax)?;
+        match data.power {
+            PowerRequest::Start | PowerRequest::Charging(_) => {
+                AfbSubCall::call_async(
+                    api,
+                    ctx.engy_api,
+                    "state",
+                    "{'action':'read'}",
+                    async_engy_cb,
+                    AsyncEngyData { session: ctx.session, qrcode: ctx.qrcode },
+                )?;
+            }
+            _ => {}
+        }
+        Ok(())
+}
+
+// shared display handles, used to restore display state once apis are ready
+#[derive(Clone, Copy)]
+pub struct PanelHandles {
+    pub session: &'static SessionHandle,
+    pub qrcode: &'static QrcodePanel,
+    pub limit: &'static LimitPanel,
+    pub auth: &'static AuthPanel,
+    pub dispatch: &'static LvglDispatch,
+    pub icons: &'static StatusIcons,
+}
+
 pub fn init_display_value(
     api: & AfbApi,
-    _widget: &'static LvglPixmap,
     config: ApiConfig,
+    panels: PanelHandles,
 ) -> Result<(), AfbError> {
 
-        AfbSubCall::call_async(api, config.auth_api,"state","{'action':'read'}", async_auth_cb, ASyncCallData{my_counter:0})?;
+        AfbSubCall::call_async(api, config.auth_api,"state","{'action':'read'}", async_auth_cb, AsyncAuthData{icons: panels.icons, session: panels.session, auth: panels.auth})?;
+        AfbSubCall::call_async(api, config.chmgr_api,"state","{'action':'read'}", async_chmgr_cb, AsyncChmgrData{session: panels.session, qrcode: panels.qrcode, limit: panels.limit, engy_api: config.engy_api})?;
         Ok(())
 }
 
@@ -435,15 +1031,59 @@ pub(crate) fn register_verbs(
     api: &mut AfbApi,
     display: &mut DisplayHandle,
     config: ApiConfig,
-) -> Result<(), AfbError> {
+    session: &'static SessionHandle,
+    locale: &'static LocaleHandle,
+    panel_config: PanelConfig,
+) -> Result<PanelHandles, AfbError> {
     // global display API event
     let event = AfbEvent::new("widget");
 
     // build panel register display callback
     display
         .set_callback(Box::new(WidgetEvtCtx { event }))
-        .draw_panel()
-        .finalize();
+        .draw_panel();
+
+    // press gestures and label fonts are read before lvgl loop starts
+    let access = panel_config.access;
+    access.bind_labels(display)?;
+    let carousel = CarouselHandle::new(display, panel_config.carousel)?;
+    let press: &'static PressEvtCtx = Box::leak(Box::new(PressEvtCtx { locale, access, carousel }));
+    bind_press(get_panel_widget(display, "Pixmap-lang")?, press)?;
+    bind_press(get_panel_widget(display, "Pixmap-carousel")?, press)?;
+    bind_press(carousel.get_area(), press)?;
+    match access.get_trigger() {
+        Some(trigger) if trigger != "Pixmap-lang" => bind_press(get_panel_widget(display, trigger)?, press)?,
+        _ => {}
+    }
+    if let Err(error) = display.finalize() {
+        return afb_error!("verb-display-finalize", "{}", error);
+    }
+    let dispatch = display.get_dispatch();
+
+    // static labels follow current language
+    locale.bind(display)?;
+    panel_config.fonts.apply(display)?;
+    let locale_verb = AfbVerb::new("locale")
+        .set_info("set display language")
+        .set_usage("'fr'")
+        .set_callback(locale_verb_cb)
+        .set_context(LocaleVerbCtx { locale })
+        .finalize()?;
+    api.add_verb(locale_verb);
+
+    let mut pixmaps = HashMap::new();
+    for widget in display.get_panel() {
+        if let Some(pixmap) = widget.as_any().downcast_ref::<LvglPixmap>() {
+            pixmaps.insert(widget.get_uid(), pixmap);
+        }
+    }
+    let asset_verb = AfbVerb::new("asset")
+        .set_info("list assets or set a pixmap to a named asset")
+        .set_usage("{'action':'SET','uid':'Pixmap-logo','asset':'logo_ocpp'}")
+        .set_callback(asset_verb_cb)
+        .set_context(AssetVerbCtx { pixmaps, dispatch })
+        .finalize()?;
+    api.add_verb(asset_verb);
 
     //------------------------------------------------------------------
 
@@ -471,6 +1111,45 @@ pub(crate) fn register_verbs(
     let auth_api = config.auth_api;
     let dbus_api = config.dbus_api;
 
+    let chart = match display.get_by_uid("Chart-power").downcast_ref::<LvglChart>() {
+        Some(widget) => widget,
+        None => {
+            return afb_error!(
+                "verb-info-widget",
+                "no widget uid:{} type:{} found in panel",
+                "Chart-power",
+                "LvglChart"
+            )
+        }
+    };
+
+    // values are greyed out when their micro service stops publishing
+    let stale_event = AfbEvent::new("stale");
+    api.add_event(stale_event);
+    let stale_verb = AfbVerb::new("stale")
+        .set_info("subscribe to stale value notification")
+        .set_usage("{'action':'subscribe'}")
+        .set_callback(subscribe_evt_cb)
+        .set_context(SubscribeEvtCtx { event: stale_event })
+        .finalize()?;
+    api.add_verb(stale_verb);
+
+    // meter labels and chart are coalesced and redrawn at most refresh_rate times per second
+    let refresh = LvglRefresh::new();
+    AfbTimer::new("refresh-timer")
+        .set_period(1000 / panel_config.refresh_rate)
+        .set_callback(refresh_timer_cb)
+        .set_context(RefreshTimerCtx { refresh, dispatch })
+        .start()?;
+
+    let freshness = panel_config.freshness;
+    let engy_stale = StaleSource::new(engy_api, freshness.engy, stale_event, refresh, dispatch)
+        .add_label(get_widget::<LvglLabel>(display, "ChargeVoltsVal")?)
+        .add_label(get_widget::<LvglLabel>(display, "ChargeEnergysVal")?)
+        .add_label(get_widget::<LvglLabel>(display, "ChargeImpsVal")?)
+        .add_label(get_widget::<LvglLabel>(display, "BatConso")?)
+        .finalize();
+
     handler_by_uid!(
         api,
         display,
@@ -479,7 +1158,9 @@ pub(crate) fn register_verbs(
         "tension",
         LvglLabel,
         MgrEvtEngyCtrl,
-        evt_nrj_cb
+        evt_nrj_cb,
+        refresh,
+        stale: engy_stale
     );
 
     handler_by_uid!(
@@ -489,8 +1170,11 @@ pub(crate) fn register_verbs(
         engy_api,
         "energy",
         LvglLabel,
-        MgrEvtEngyCtrl,
-        evt_nrj_cb
+        MgrEvtEnergyCtrl,
+        evt_energy_cb,
+        session,
+        refresh,
+        stale: engy_stale
     );
 
     handler_by_uid!(
@@ -500,8 +1184,12 @@ pub(crate) fn register_verbs(
         engy_api,
         "current",
         LvglLabel,
-        MgrEvtEngyCtrl,
-        evt_nrj_cb
+        MgrEvtCurveCtrl,
+        evt_current_cb,
+        session,
+        chart,
+        refresh,
+        stale: engy_stale
     );
 
     handler_by_uid!(
@@ -511,8 +1199,12 @@ pub(crate) fn register_verbs(
         engy_api,
         "power",
         LvglLabel,
-        MgrEvtEngyCtrl,
-        evt_nrj_cb
+        MgrEvtCurveCtrl,
+        evt_power_cb,
+        session,
+        chart,
+        refresh,
+        stale: engy_stale
     );
 
     let widget_charge = match display.get_by_uid("Pixmap-charge-status").downcast_ref::<LvglPixmap>() {
@@ -566,33 +1258,97 @@ pub(crate) fn register_verbs(
         }
     };
 
+    let summary = SummaryScreen::new(display)?;
+    let fault = FaultScreen::new(display, panel_config.faults, locale)?;
+    let icons = StatusIcons::new(display, panel_config.icons, locale)?;
+    let reservation = ReservationPanel::new(display, icons, locale)?;
+    let limit = LimitPanel::new(display, panel_config.evse_imax, panel_config.cable_imax, locale)?;
+    let auth = AuthPanel::new(display, locale)?;
+    access.set_icons(icons)?;
+    let access_verb = AfbVerb::new("accessibility")
+        .set_info("large fonts, high contrast and status text on/off")
+        .set_usage("{'action':'ON'}")
+        .set_callback(access_verb_cb)
+        .set_context(AccessVerbCtx { access })
+        .finalize()?;
+    api.add_verb(access_verb);
+
+    let backlight = BacklightHandle::new(display, panel_config.backlight)?;
+    let backlight_verb = AfbVerb::new("backlight")
+        .set_info("wake screen (ON) or force screensaver (OFF)")
+        .set_usage("{'action':'ON'}")
+        .set_callback(backlight_verb_cb)
+        .set_context(BacklightCtx { backlight })
+        .finalize()?;
+    api.add_verb(backlight_verb);
+
+    AfbTimer::new("backlight-timer")
+        .set_period(500)
+        .set_callback(backlight_timer_cb)
+        .set_context(BacklightCtx { backlight })
+        .start()?;
+
+    AfbTimer::new("carousel-timer")
+        .set_period(1000)
+        .set_callback(carousel_timer_cb)
+        .set_context(CarouselTimerCtx { carousel })
+        .start()?;
+
+    let qrcode = QrcodePanel::new(display, panel_config.qrcode, session)?;
+    let qrcode_verb = AfbVerb::new("qrcode")
+        .set_info("update qrcode payload template")
+        .set_usage("'https://example.com/pay?station={station}&session={session}'")
+        .set_callback(qrcode_verb_cb)
+        .set_context(QrcodeVerbCtx { qrcode })
+        .finalize()?;
+    api.add_verb(qrcode_verb);
+
+    let chmgr_stale = StaleSource::new(chmgr_api, freshness.chmgr, stale_event, refresh, dispatch)
+        .add_pixmap(widget_charge)
+        .add_pixmap(widget_plug_status)
+        .finalize();
+    let auth_stale = StaleSource::new(auth_api, freshness.auth, stale_event, refresh, dispatch)
+        .add_pixmap(get_widget::<LvglPixmap>(display, "Pixmap-auth-status")?)
+        .finalize();
+    let watchdog = StaleWatchdog::new(vec![engy_stale, chmgr_stale, auth_stale]);
+
     let charger_handler = AfbEvtHandler::new("Charger_manager")
         .set_info("Charger manager")
         .set_pattern(to_static_str(format!("{}/{}",chmgr_api, "*")))
         .set_callback(evt_chmgr_cb)
-        .set_context(MgrEvtChmgrCtrl{ widget_charge, widget_plug_status, widget_iec_status })
+        .set_context(MgrEvtChmgrCtrl{ widget_iec_status, summary, fault, reservation, limit, auth, session, qrcode, chart, icons, backlight, carousel, engy_api, stale: chmgr_stale, dispatch })
         .finalize()?;
 
+    // only nfc reader events, other dbus events neither wake screen nor touch reader icon
     let nfc_handler = AfbEvtHandler::new("nfc_manager")
         .set_info("nfc manager")
-        .set_pattern(to_static_str(format!("{}/{}",dbus_api, "*")))
+        .set_pattern(to_static_str(format!("{}/{}",dbus_api, "*nfc*")))
         .set_callback(evt_nfc_cb)
-        .set_context(MgrEvtNfcCtrl{ widget_nfc_status })
+        .set_context(MgrEvtNfcCtrl{ widget_nfc_status, backlight, carousel, dispatch })
         .finalize()?;
 
     api.add_evt_handler(charger_handler);
     api.add_evt_handler(nfc_handler);
 
-    handler_by_uid!(
-        api,
-        display,
-        "Pixmap-auth-status",
-        auth_api,
-        "*",
-        LvglPixmap,
-        MgrEvtAuthCrl,
-        evt_auth_cb
-    );
+    // grid meter events, handlers stay silent when meter is absent
+    let grid = GridPanel::new(display)?;
+    for verb in [panel_config.grid.avail_verb, panel_config.grid.over_verb] {
+        let grid_handler = AfbEvtHandler::new(to_static_str(format!("grid_{}", verb)))
+            .set_info("grid meter")
+            .set_pattern(to_static_str(format!("{}/{}", engy_api, verb)))
+            .set_callback(evt_grid_cb)
+            .set_context(MgrEvtGridCtrl { grid })
+            .finalize()?;
+        api.add_evt_handler(grid_handler);
+    }
+
+    let auth_handler = AfbEvtHandler::new("Pixmap-auth-status")
+        .set_info("Pixmap auth status")
+        .set_pattern(to_static_str(format!("{}/{}", auth_api, "*")))
+        .set_callback(evt_auth_cb)
+        .set_context(MgrEvtAuthCrl { session, auth, icons, backlight, carousel, auth_api, stale: auth_stale, dispatch })
+        .finalize()?;
+    api.add_evt_handler(auth_handler);
 
     //------------------------------------------------------------------
 
@@ -638,8 +1394,68 @@ pub(crate) fn register_verbs(
     AfbTimer::new("clock-timer")
         .set_period(60000)
         .set_callback(timer_callback)
-        .set_context(TimerCtx { time, date })
+        .set_context(TimerCtx { time, date, dispatch })
         .start()?;
 
+    let session_time = match display.get_by_uid("session-time").downcast_ref::<LvglLabel>() {
+        Some(widget) => widget,
+        None => {
+            return afb_error!(
+                "conf-session-time-widget",
+                "no widget uid: session-time  type:LvglLabel found in panel",
+            )
+        }
+    };
+
+    AfbTimer::new("panel-timer")
+        .set_period(1000)
+        .set_callback(panel_timer_cb)
+        .set_context(PanelTimerCtx { reservation, limit, watchdog })
+        .start()?;
+
+    let session_cost = match display.get_by_uid("session-cost").downcast_ref::<LvglLabel>() {
+        Some(widget) => widget,
+        None => {
+            return afb_error!(
+                "conf-session-cost-widget",
+                "no widget uid: session-cost  type:LvglLabel found in panel",
+            )
+        }
+    };
+
+    // network icons follow local interfaces state
+    let network_period = panel_config.network.period.max(1) * 1000;
+    let network = NetworkPanel::new(display, panel_config.network)?;
+    network.poll()?;
+    AfbTimer::new("network-timer")
+        .set_period(network_period)
+        .set_callback(network_timer_cb)
+        .set_context(NetworkTimerCtx { network })
+        .start()?;
+
+    AfbTimer::new("session-timer")
+        .set_period(1000)
+        .set_callback(session_timer_cb)
+        .set_context(SessionTimerCtx { widget: session_time, cost: session_cost, session, summary, refresh })
+        .start()?;
+
+    Ok(PanelHandles { session, qrcode, limit, auth, dispatch, icons })
+}
+
+// theme switch verb and day/night schedule, switches are posted to lvgl thread
+pub(crate) fn register_theme(api: &mut AfbApi, theme: &'static ThemeHandle) -> Result<(), AfbError> {
+    let theme_verb = AfbVerb::new("theme")
+        .set_info("list named themes or switch to one")
+        .set_usage("{'action':'SET','name':'night'}")
+        .set_callback(theme_verb_cb)
+        .set_context(ThemeVerbCtx { theme })
+        .finalize()?;
+    api.add_verb(theme_verb);
+
+    AfbTimer::new("theme-timer")
+        .set_period(theme.get_period())
+        .set_callback(theme_timer_cb)
+        .set_context(ThemeTimerCtx { theme })
+        .start()?;
     Ok(())
 }
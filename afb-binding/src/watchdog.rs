@@ -0,0 +1,212 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ */
+
+use crate::prelude::*;
+use afbv4::prelude::*;
+use display_lvgl_gui::prelude::*;
+use serde::Deserialize;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+// binding config 'freshness' object, per micro service timeout in seconds (0=disabled)
+#[derive(Deserialize, Debug, Clone, Copy, Default)]
+pub struct FreshnessConfig {
+    #[serde(default)]
+    pub engy: u32,
+    #[serde(default)]
+    pub chmgr: u32,
+    #[serde(default)]
+    pub auth: u32,
+}
+
+impl FreshnessConfig {
+    pub fn from_jsonc(jfresh: JsoncObj) -> Result<Self, AfbError> {
+        match serde_json::from_str::<FreshnessConfig>(jfresh.to_string().as_str()) {
+            Ok(value) => Ok(value),
+            Err(error) => afb_error!("freshness-config-fail", "invalid freshness config:{}", error),
+        }
+    }
+}
+
+struct StaleState {
+    updated: Duration,
+    stale: bool,
+}
+
+impl StaleState {
+    fn new(now: Duration) -> Self {
+        StaleState { updated: now, stale: false }
+    }
+
+    // return true when a stale source becomes fresh again
+    fn touch(&mut self, now: Duration) -> bool {
+        self.updated = now;
+        let recovered = self.stale;
+        self.stale = false;
+        recovered
+    }
+
+    // return true when source turns stale, a zero timeout disables the check
+    fn check(&mut self, now: Duration, timeout: Duration) -> bool {
+        if timeout.is_zero() || self.stale {
+            return false;
+        }
+        self.stale = now.saturating_sub(self.updated) > timeout;
+        self.stale
+    }
+}
+
+// widgets fed by one micro service, greyed out when it stops publishing
+pub struct StaleSource {
+    api: &'static str,
+    timeout: Duration,
+    event: &'static AfbEvent,
+    refresh: &'static LvglRefresh,
+    dispatch: &'static LvglDispatch,
+    labels: Vec<&'static LvglLabel>,
+    pixmaps: Vec<&'static LvglPixmap>,
+    data_set: Mutex<StaleState>,
+}
+
+impl StaleSource {
+    pub fn new(
+        api: &'static str,
+        timeout: u32,
+        event: &'static AfbEvent,
+        refresh: &'static LvglRefresh,
+        dispatch: &'static LvglDispatch,
+    ) -> Self {
+        StaleSource {
+            api,
+            timeout: Duration::from_secs(timeout as u64),
+            event,
+            refresh,
+            dispatch,
+            labels: Vec::new(),
+            pixmaps: Vec::new(),
+            data_set: Mutex::new(StaleState::new(time_now())),
+        }
+    }
+
+    // labels are replaced by '--' when stale, they should be updated through refresh queue
+    pub fn add_label(mut self, label: &'static LvglLabel) -> Self {
+        self.labels.push(label);
+        self
+    }
+
+    pub fn add_pixmap(mut self, pixmap: &'static LvglPixmap) -> Self {
+        self.pixmaps.push(pixmap);
+        self
+    }
+
+    pub fn finalize(self) -> &'static Self {
+        Box::leak(Box::new(self))
+    }
+
+    fn get_state(&self) -> Result<MutexGuard<'_, StaleState>, AfbError> {
+        match self.data_set.lock() {
+            Ok(guard) => Ok(guard),
+            Err(_) => afb_error!("stale-state-lock", "fail to lock stale state"),
+        }
+    }
+
+    fn set_stale(&'static self, stale: bool) {
+        if stale {
+            for label in &self.labels {
+                self.refresh.set_label(*label, "--".to_string());
+            }
+        }
+        self.dispatch.post(move || {
+            for label in &self.labels {
+                label.set_dimmed(stale);
+            }
+            for pixmap in &self.pixmaps {
+                pixmap.set_dimmed(stale);
+            }
+        });
+        self.event.push(format!("{{'api':'{}','stale':{}}}", self.api, stale));
+    }
+
+    // called on every source event before widgets get their new value
+    pub fn touch(&'static self) -> Result<(), AfbError> {
+        let mut state = self.get_state()?;
+        if state.touch(time_now()) {
+            self.set_stale(false);
+        }
+        Ok(())
+    }
+
+    pub fn check(&'static self, now: Duration) -> Result<(), AfbError> {
+        if self.timeout.is_zero() {
+            return Ok(());
+        }
+        let mut state = self.get_state()?;
+        if state.check(now, self.timeout) {
+            self.set_stale(true);
+        }
+        Ok(())
+    }
+}
+
+pub struct StaleWatchdog {
+    sources: Vec<&'static StaleSource>,
+}
+
+impl StaleWatchdog {
+    pub fn new(sources: Vec<&'static StaleSource>) -> &'static Self {
+        Box::leak(Box::new(StaleWatchdog { sources }))
+    }
+
+    pub fn tick(&self) -> Result<(), AfbError> {
+        let now = time_now();
+        for source in &self.sources {
+            source.check(now)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_freshness_disables_watchdog() {
+        let mut state = StaleState::new(Duration::from_secs(10));
+        assert!(!state.check(Duration::from_secs(100_000), Duration::ZERO));
+        assert!(!state.stale);
+    }
+
+    #[test]
+    fn stale_after_timeout_only_once() {
+        let timeout = Duration::from_secs(30);
+        let mut state = StaleState::new(Duration::from_secs(10));
+        assert!(!state.check(Duration::from_secs(40), timeout));
+        assert!(state.check(Duration::from_secs(41), timeout));
+        assert!(!state.check(Duration::from_secs(90), timeout));
+        assert!(state.stale);
+    }
+
+    #[test]
+    fn stale_to_fresh_transition() {
+        let timeout = Duration::from_secs(30);
+        let mut state = StaleState::new(Duration::from_secs(0));
+        assert!(!state.touch(Duration::from_secs(5)));
+        assert!(state.check(Duration::from_secs(60), timeout));
+
+        // first event after stale restores widgets, next ones do not
+        assert!(state.touch(Duration::from_secs(61)));
+        assert!(!state.touch(Duration::from_secs(62)));
+        assert!(!state.check(Duration::from_secs(80), timeout));
+        assert!(state.check(Duration::from_secs(93), timeout));
+    }
+}
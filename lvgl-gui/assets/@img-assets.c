@@ -1,43 +1,3 @@
+// template, block filled by capi/build-assets.rs into OUT_DIR
 //@BEGIN_IMG_imgS@
-#include "ethernet_off.c"
-#include "nfc_fail.c"
-#include "battery_charge_on.c"
-#include "ethernet_on.c"
-#include "plug_error.c"
-#include "station_out_of_order.c"
-#include "station_reserved.c"
-#include "reception_off.c"
-#include "station_available.c"
-#include "btn_start.c"
-#include "currency_exchange.c"
-#include "plug_disconnected.c"
-#include "wifi_off.c"
-#include "wifi_on.c"
-#include "lightning_charge.c"
-#include "battery_charge_off.c"
-#include "btn_idle.c"
-#include "battery_full.c"
-#include "station_charging.c"
-#include "nfc_off.c"
-#include "tux_evsex150.c"
-#include "nfc_on.c"
-#include "calendar3.c"
-#include "translate.c"
-#include "btn_stop.c"
-#include "nfc_idle.c"
-#include "stopwatch.c"
-#include "nfc_done.c"
-#include "clock.c"
-#include "info_circle_fill.c"
-#include "plug_connected_unlocked.c"
-#include "logo_iot_bzh_flat.c"
-#include "logo_valeo.c"
-#include "nfc_pending.c"
-#include "station_completed.c"
-#include "station_pending_autho.c"
-#include "logo_ocpp.c"
-#include "tux_evsex40.c"
-#include "plug_connected_locked.c"
-#include "reception_on.c"
-#include "plug_unknow.c"
 //@END_IMG_imgS@
\ No newline at end of file
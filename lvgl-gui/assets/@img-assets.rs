@@ -1,48 +1,14 @@
+// template, blocks filled by capi/build-assets.rs into OUT_DIR
 
 pub struct AssetPixmap;
 #[allow(dead_code)]
 impl AssetPixmap {
 //@BEGIN_IMG_imgS@
-    impl_static_imgbin! (ethernet_off, img_ethernet_off);
-    impl_static_imgbin! (nfc_fail, img_nfc_fail);
-    impl_static_imgbin! (battery_charge_on, img_battery_charge_on);
-    impl_static_imgbin! (ethernet_on, img_ethernet_on);
-    impl_static_imgbin! (plug_error, img_plug_error);
-    impl_static_imgbin! (station_out_of_order, img_station_out_of_order);
-    impl_static_imgbin! (station_reserved, img_station_reserved);
-    impl_static_imgbin! (reception_off, img_reception_off);
-    impl_static_imgbin! (station_available, img_station_available);
-    impl_static_imgbin! (btn_start, img_btn_start);
-    impl_static_imgbin! (currency_exchange, img_currency_exchange);
-    impl_static_imgbin! (plug_disconnected, img_plug_disconnected);
-    impl_static_imgbin! (wifi_off, img_wifi_off);
-    impl_static_imgbin! (wifi_on, img_wifi_on);
-    impl_static_imgbin! (lightning_charge, img_lightning_charge);
-    impl_static_imgbin! (battery_charge_off, img_battery_charge_off);
-    impl_static_imgbin! (btn_idle, img_btn_idle);
-    impl_static_imgbin! (battery_full, img_battery_full);
-    impl_static_imgbin! (station_charging, img_station_charging);
-    impl_static_imgbin! (nfc_off, img_nfc_off);
-    impl_static_imgbin! (tux_evsex150, img_tux_evsex150);
-    impl_static_imgbin! (nfc_on, img_nfc_on);
-    impl_static_imgbin! (calendar3, img_calendar3);
-    impl_static_imgbin! (translate, img_translate);
-    impl_static_imgbin! (btn_stop, img_btn_stop);
-    impl_static_imgbin! (nfc_idle, img_nfc_idle);
-    impl_static_imgbin! (stopwatch, img_stopwatch);
-    impl_static_imgbin! (nfc_done, img_nfc_done);
-    impl_static_imgbin! (clock, img_clock);
-    impl_static_imgbin! (info_circle_fill, img_info_circle_fill);
-    impl_static_imgbin! (plug_connected_unlocked, img_plug_connected_unlocked);
-    impl_static_imgbin! (logo_iot_bzh_flat, img_logo_iot_bzh_flat);
-    impl_static_imgbin! (nfc_pending, img_nfc_pending);
-    impl_static_imgbin! (station_completed, img_station_completed);
-    impl_static_imgbin! (station_pending_autho, img_station_pending_autho);
-    impl_static_imgbin! (logo_ocpp, img_logo_ocpp);
-    impl_static_imgbin! (tux_evsex40, img_tux_evsex40);
-    impl_static_imgbin! (plug_connected_locked, img_plug_connected_locked);
-    impl_static_imgbin! (reception_on, img_reception_on);
-    impl_static_imgbin! (plug_unknow, img_plug_unknow);
 //@END_IMG_imgS@
-    impl_static_imgbin! (logo_valeo, img_logo_valeo);
-}
\ No newline at end of file
+}
+
+// compiled asset name table, used by AssetPixmap::by_name
+pub(crate) const IMG_ASSETS: &[(&str, fn() -> &'static LvglImgDsc)] = &[
+//@BEGIN_IMG_NAMES@
+//@END_IMG_NAMES@
+];
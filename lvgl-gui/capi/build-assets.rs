@@ -0,0 +1,379 @@
+/*
+ * Copyright (C) 2015-2023 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Redpesk interface code/config use MIT License and can be freely copy/modified even within proprietary code
+ * License: $RP_BEGIN_LICENSE$ SPDX:MIT https://opensource.org/licenses/MIT $RP_END_LICENSE$
+ *
+*/
+
+// convert assets/*.png|svg into lvgl C image descriptors and generate @img-assets.{c,rs} from their templates
+// everything is written into OUT_DIR, source assets/ dir is never modified
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const IMG_MARKS: (&str, &str) = ("//@BEGIN_IMG_imgS@", "//@END_IMG_imgS@");
+const NAME_MARKS: (&str, &str) = ("//@BEGIN_IMG_NAMES@", "//@END_IMG_NAMES@");
+
+// LVGL_IMG_CF=true_color drops alpha channel (smaller assets for opaque logos)
+#[derive(Clone, Copy, PartialEq)]
+pub enum ImgFormat {
+    TrueColor,
+    TrueColorAlpha,
+}
+
+impl ImgFormat {
+    pub fn from_env(value: Option<String>) -> Self {
+        match value.as_deref() {
+            Some("true_color") => ImgFormat::TrueColor,
+            _ => ImgFormat::TrueColorAlpha,
+        }
+    }
+
+    fn lvgl_cf(&self) -> &'static str {
+        match self {
+            ImgFormat::TrueColor => "LV_IMG_CF_TRUE_COLOR",
+            ImgFormat::TrueColorAlpha => "LV_IMG_CF_TRUE_COLOR_ALPHA",
+        }
+    }
+
+    fn px_size(&self) -> &'static str {
+        match self {
+            ImgFormat::TrueColor => "LV_COLOR_SIZE / 8",
+            ImgFormat::TrueColorAlpha => "LV_IMG_PX_SIZE_ALPHA_BYTE",
+        }
+    }
+}
+
+// straight (non premultiplied) RGBA8888
+struct RgbaImage {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+fn decode_png(path: &Path) -> Result<RgbaImage, String> {
+    let file = fs::File::open(path).map_err(|error| error.to_string())?;
+    let mut decoder = png::Decoder::new(file);
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info().map_err(|error| error.to_string())?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut buffer)
+        .map_err(|error| error.to_string())?;
+    let data = &buffer[..info.buffer_size()];
+
+    let pixels = match info.color_type {
+        png::ColorType::Rgba => data.to_vec(),
+        png::ColorType::Rgb => data
+            .chunks(3)
+            .flat_map(|px| [px[0], px[1], px[2], 0xff])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => data
+            .chunks(2)
+            .flat_map(|px| [px[0], px[0], px[0], px[1]])
+            .collect(),
+        png::ColorType::Grayscale => data.iter().flat_map(|px| [*px, *px, *px, 0xff]).collect(),
+        png::ColorType::Indexed => return Err("indexed png not expanded".to_string()),
+    };
+    Ok(RgbaImage {
+        width: info.width,
+        height: info.height,
+        pixels,
+    })
+}
+
+// svg is rendered at its intrinsic size
+fn decode_svg(path: &Path) -> Result<RgbaImage, String> {
+    let data = fs::read(path).map_err(|error| error.to_string())?;
+    let tree = resvg::usvg::Tree::from_data(&data, &resvg::usvg::Options::default())
+        .map_err(|error| error.to_string())?;
+    let size = tree.size().to_int_size();
+    let mut pixmap = match resvg::tiny_skia::Pixmap::new(size.width(), size.height()) {
+        Some(value) => value,
+        None => return Err("invalid svg size".to_string()),
+    };
+    resvg::render(
+        &tree,
+        resvg::tiny_skia::Transform::default(),
+        &mut pixmap.as_mut(),
+    );
+
+    let pixels = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|px| {
+            let color = px.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+    Ok(RgbaImage {
+        width: size.width(),
+        height: size.height(),
+        pixels,
+    })
+}
+
+// one section per LV_COLOR_DEPTH, same layout as lvgl online image converter
+fn write_section(
+    out: &mut String,
+    image: &RgbaImage,
+    format: ImgFormat,
+    condition: &str,
+    comment: &str,
+    encode: fn(u8, u8, u8) -> Vec<u8>,
+) {
+    out.push_str(&format!("#if {}\n  /*{}*/\n", condition, comment));
+    for row in image.pixels.chunks((image.width * 4) as usize) {
+        out.push_str("  ");
+        for px in row.chunks(4) {
+            let mut bytes = encode(px[0], px[1], px[2]);
+            match format {
+                ImgFormat::TrueColorAlpha => bytes.push(px[3]),
+                // 32 bit true color keeps a fixed 0xff padding byte
+                ImgFormat::TrueColor if bytes.len() == 3 => bytes.push(0xff),
+                ImgFormat::TrueColor => {}
+            }
+            for byte in bytes {
+                out.push_str(&format!("0x{:02x}, ", byte));
+            }
+        }
+        out.push('\n');
+    }
+    out.push_str("#endif\n");
+}
+
+fn rgb565(red: u8, green: u8, blue: u8) -> u16 {
+    ((red as u16 >> 3) << 11) | ((green as u16 >> 2) << 5) | (blue as u16 >> 3)
+}
+
+fn to_c_array(name: &str, image: &RgbaImage, format: ImgFormat) -> String {
+    let attribute = format!("LV_ATTRIBUTE_IMG_{}", name.to_uppercase());
+    let mut out = String::new();
+    out.push_str("#ifdef __has_include\n    #if __has_include(\"lvgl.h\")\n        #ifndef LV_LVGL_H_INCLUDE_SIMPLE\n            #define LV_LVGL_H_INCLUDE_SIMPLE\n        #endif\n    #endif\n#endif\n\n");
+    out.push_str("#if defined(LV_LVGL_H_INCLUDE_SIMPLE)\n    #include \"lvgl.h\"\n#else\n    #include \"lvgl/lvgl.h\"\n#endif\n\n\n");
+    out.push_str("#ifndef LV_ATTRIBUTE_MEM_ALIGN\n#define LV_ATTRIBUTE_MEM_ALIGN\n#endif\n\n");
+    out.push_str(&format!(
+        "#ifndef {}\n#define {}\n#endif\n\n",
+        attribute, attribute
+    ));
+    out.push_str(&format!(
+        "const LV_ATTRIBUTE_MEM_ALIGN LV_ATTRIBUTE_LARGE_CONST {} uint8_t {}_map[] = {{\n",
+        attribute, name
+    ));
+
+    let alpha = if format == ImgFormat::TrueColorAlpha {
+        "Alpha 8 bit, "
+    } else {
+        ""
+    };
+    write_section(
+        &mut out,
+        image,
+        format,
+        "LV_COLOR_DEPTH == 1 || LV_COLOR_DEPTH == 8",
+        &format!(
+            "Pixel format: {}Red: 3 bit, Green: 3 bit, Blue: 2 bit",
+            alpha
+        ),
+        |red, green, blue| vec![(red & 0xe0) | ((green & 0xe0) >> 3) | (blue >> 6)],
+    );
+    write_section(
+        &mut out,
+        image,
+        format,
+        "LV_COLOR_DEPTH == 16 && LV_COLOR_16_SWAP == 0",
+        &format!(
+            "Pixel format: {}Red: 5 bit, Green: 6 bit, Blue: 5 bit",
+            alpha
+        ),
+        |red, green, blue| rgb565(red, green, blue).to_le_bytes().to_vec(),
+    );
+    write_section(
+        &mut out,
+        image,
+        format,
+        "LV_COLOR_DEPTH == 16 && LV_COLOR_16_SWAP != 0",
+        &format!("Pixel format: {}Red: 5 bit, Green: 6 bit, Blue: 5 bit  BUT the 2  color bytes are swapped", alpha),
+        |red, green, blue| rgb565(red, green, blue).to_be_bytes().to_vec(),
+    );
+    write_section(
+        &mut out,
+        image,
+        format,
+        "LV_COLOR_DEPTH == 32",
+        &format!(
+            "Pixel format: {}Red: 8 bit, Green: 8 bit, Blue: 8 bit",
+            if alpha.is_empty() {
+                "Fix 0xFF: 8 bit, "
+            } else {
+                alpha
+            }
+        ),
+        |red, green, blue| vec![blue, green, red],
+    );
+    out.push_str("};\n\n");
+
+    out.push_str(&format!("const lv_img_dsc_t img_{} = {{\n", name));
+    out.push_str(&format!("  .header.cf = {},\n", format.lvgl_cf()));
+    out.push_str("  .header.always_zero = 0,\n  .header.reserved = 0,\n");
+    out.push_str(&format!(
+        "  .header.w = {},\n  .header.h = {},\n",
+        image.width, image.height
+    ));
+    out.push_str(&format!(
+        "  .data_size = {} * {},\n",
+        image.width * image.height,
+        format.px_size()
+    ));
+    out.push_str(&format!("  .data = {}_map,\n}};\n", name));
+    out
+}
+
+// only touch files when content changes, cargo would otherwise rerun build on every compilation
+fn write_if_changed(path: &Path, content: &str) {
+    if let Ok(current) = fs::read_to_string(path) {
+        if current == content {
+            return;
+        }
+    }
+    fs::write(path, content)
+        .unwrap_or_else(|error| panic!("fail to write {}: {}", path.display(), error));
+}
+
+fn replace_block(content: &str, marks: (&str, &str), lines: &[String], template: &Path) -> String {
+    let (begin, end) = match (content.find(marks.0), content.find(marks.1)) {
+        (Some(begin), Some(end)) if begin < end => (begin + marks.0.len(), end),
+        _ => panic!(
+            "missing {} / {} markers in {}",
+            marks.0,
+            marks.1,
+            template.display()
+        ),
+    };
+    let mut block = String::from("\n");
+    for line in lines {
+        block.push_str(line);
+        block.push('\n');
+    }
+    format!("{}{}{}", &content[..begin], block, &content[end..])
+}
+
+fn read_template(path: &Path) -> String {
+    fs::read_to_string(path)
+        .unwrap_or_else(|error| panic!("fail to read {}: {}", path.display(), error))
+}
+
+fn is_newer(source: &Path, target: &Path) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|meta| meta.modified()).ok();
+    match (modified(source), modified(target)) {
+        (Some(source), Some(target)) => source > target,
+        _ => true,
+    }
+}
+
+// 'tux-evsex150.png' -> 'tux_evsex150'
+fn asset_name(path: &Path) -> String {
+    path.file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .chars()
+        .map(|char| {
+            if char.is_ascii_alphanumeric() {
+                char.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+// exported 'const lv_img_dsc_t img_<name>' C files within dir
+fn img_names(dir: &Path) -> Vec<String> {
+    let mut names = Vec::new();
+    let entries = match fs::read_dir(dir) {
+        Ok(value) => value,
+        Err(_) => return names,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let file = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        if !file.ends_with(".c") || file.starts_with('@') {
+            continue;
+        }
+        let name = file.trim_end_matches(".c").to_string();
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        if content.contains(&format!("const lv_img_dsc_t img_{} =", name)) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+// converted files sit in out_dir, which is searched first by '#include "<name>.c"' from generated @img-assets.c
+pub fn generate_assets(dir: &Path, out_dir: &Path, format: ImgFormat) {
+    let mut sources: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap_or_else(|error| panic!("fail to read asset dir {}: {}", dir.display(), error))
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    sources.sort();
+
+    // a LVGL_IMG_CF change has to regenerate every image whatever its timestamp
+    let stamp = out_dir.join("@img-format");
+    let force = fs::read_to_string(&stamp).ok().as_deref() != Some(format.lvgl_cf());
+
+    for source in &sources {
+        let image = match source.extension().and_then(|ext| ext.to_str()) {
+            Some("png") => decode_png(source),
+            Some("svg") => decode_svg(source),
+            _ => continue,
+        };
+        let name = asset_name(source);
+        let target = out_dir.join(format!("{}.c", name));
+        if !force && !is_newer(source, &target) {
+            continue;
+        }
+        match image {
+            Ok(image) => write_if_changed(&target, &to_c_array(&name, &image, format)),
+            Err(error) => println!(
+                "cargo:warning=asset {} ignored: {}",
+                source.display(),
+                error
+            ),
+        }
+    }
+    write_if_changed(&stamp, format.lvgl_cf());
+
+    // every C file exporting an image becomes an AssetPixmap accessor, converted ones win over hand made
+    let mut names = img_names(dir);
+    names.extend(img_names(out_dir));
+    names.sort();
+    names.dedup();
+
+    let includes: Vec<String> = names
+        .iter()
+        .map(|name| format!("#include \"{}.c\"", name))
+        .collect();
+    let accessors: Vec<String> = names
+        .iter()
+        .map(|name| format!("    impl_static_imgbin! ({}, img_{});", name, name))
+        .collect();
+    let entries: Vec<String> = names
+        .iter()
+        .map(|name| format!("    (\"{}\", AssetPixmap::{}),", name, name))
+        .collect();
+
+    let template = dir.join("@img-assets.c");
+    let content = replace_block(&read_template(&template), IMG_MARKS, &includes, &template);
+    write_if_changed(&out_dir.join("@img-assets.c"), &content);
+
+    let template = dir.join("@img-assets.rs");
+    let content = replace_block(&read_template(&template), IMG_MARKS, &accessors, &template);
+    let content = replace_block(&content, NAME_MARKS, &entries, &template);
+    write_if_changed(&out_dir.join("@img-assets.rs"), &content);
+}
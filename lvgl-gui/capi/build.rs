@@ -8,10 +8,25 @@
 */
 extern crate bindgen;
 use std::env;
+use std::path::Path;
+
+#[path = "build-assets.rs"]
+mod assets;
 
 fn main() {
     // invalidate the built crate whenever the wrapper changes
     println!("cargo:rerun-if-changed=capi/capi-map.c");
+    println!("cargo:rerun-if-changed=assets");
+    println!("cargo:rerun-if-env-changed=LVGL_IMG_CF");
+
+    // png/svg dropped into assets/ get their C descriptor and AssetPixmap accessor within OUT_DIR
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let asset_dir = format!("{}/assets", env::var("CARGO_MANIFEST_DIR").unwrap());
+    assets::generate_assets(
+        Path::new(&asset_dir),
+        Path::new(&out_dir),
+        assets::ImgFormat::from_env(env::var("LVGL_IMG_CF").ok()),
+    );
     println!("cargo:rustc-link-search=/usr/local/lib64");
     println!("cargo:rustc-link-arg=-llvgl");
     println!("cargo:rustc-link-arg=-llv_drivers");
@@ -52,11 +67,14 @@ fn main() {
         .header("capi/capi-map.c")
         .clang_arg("-I/usr/local/include/lvgl")
         .clang_arg("-I/usr/include/lvgl")
+        .clang_arg(format!("-I{}", out_dir))
+        .clang_arg(format!("-I{}", asset_dir))
         .raw_line(header)
         .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
         .derive_debug(false)
         .layout_tests(false)
         .allowlist_item("img_.*")
+        .allowlist_function("gui_.*")
         .blocklist_type("lv_img_dsc_t") // defined in lvgl-rclib
         .generate()
         .expect("Unable to generate _capi-map.rs");
@@ -69,6 +87,8 @@ fn main() {
     cc::Build::new()
         .file("capi/capi-map.c")
         .define("USE_GTK", defined.as_str())
+        .include(&out_dir)
+        .include(&asset_dir)
         .include("/usr/local/include/lvgl")
         .include("/usr/local/include")
         .include("/usr/include/lvgl")
@@ -16,10 +16,275 @@
  */
 
 // include your LVGL compiled asset
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <strings.h>
 #include "lvgl/lvgl.h"
-#include "../assets/@img-assets.c"
+#if LV_USE_PNG
+#include "lvgl/src/extra/libs/png/lodepng.h"
+#endif
+// generated by capi/build-assets.rs within OUT_DIR
+#include "@img-assets.c"
 
 // include here after any C function to import
 // dont forget to update build.rs allowed_item list
 
+// generic object helpers for widgets not wrapped by lvgl-rclib
+void gui_obj_set_hidden(void *handle, bool hidden) {
+    if (hidden) lv_obj_add_flag((lv_obj_t*)handle, LV_OBJ_FLAG_HIDDEN);
+    else lv_obj_clear_flag((lv_obj_t*)handle, LV_OBJ_FLAG_HIDDEN);
+}
 
+// grey out widget while its value is not trusted
+void gui_obj_set_dimmed(void *handle, bool dimmed) {
+    lv_obj_set_style_opa((lv_obj_t*)handle, dimmed ? LV_OPA_40 : LV_OPA_COVER, 0);
+}
+
+// recolor image pixels, opa=0 restores original colors
+void gui_obj_set_tint(void *handle, uint8_t red, uint8_t green, uint8_t blue, uint8_t opa) {
+    lv_obj_set_style_img_recolor((lv_obj_t*)handle, lv_color_make(red, green, blue), 0);
+    lv_obj_set_style_img_recolor_opa((lv_obj_t*)handle, opa, 0);
+}
+
+// restyle existing widgets once default theme colors/font changed
+void gui_theme_refresh(void) {
+    lv_obj_report_style_change(NULL);
+    lv_obj_invalidate(lv_scr_act());
+}
+
+// theme switch after startup, same as lvgl-rclib set_theme without owning the display handle
+void gui_theme_set(uint8_t pr, uint8_t pg, uint8_t pb, uint8_t sr, uint8_t sg, uint8_t sb, bool dark, const void *font) {
+    lv_disp_t *disp = lv_disp_get_default();
+    if (!disp) return;
+    lv_theme_t *theme = lv_theme_default_init(disp, lv_color_make(pr, pg, pb), lv_color_make(sr, sg, sb), dark, (const lv_font_t*)font);
+    lv_disp_set_theme(disp, theme);
+    gui_theme_refresh();
+}
+
+// milliseconds since last touch on default display
+uint32_t gui_inactive_time(void) {
+    return lv_disp_get_inactive_time(NULL);
+}
+
+// screensaver stops redraw, touch input keeps being read and full screen is redrawn on resume
+void gui_refresh_pause(bool pause) {
+    lv_disp_t *disp = lv_disp_get_default();
+    if (!disp || !disp->refr_timer) return;
+    if (pause) {
+        lv_timer_pause(disp->refr_timer);
+    } else {
+        lv_timer_resume(disp->refr_timer);
+        lv_obj_invalidate(lv_scr_act());
+    }
+}
+
+// runtime fonts: '.ttf' through tiny_ttf, anything else as lvgl binary font (lv_font_conv --format bin)
+void *gui_font_load(const char *path, lv_coord_t size) {
+    const char *ext = strrchr(path, '.');
+    if (ext && strcasecmp(ext, ".ttf") == 0) {
+#if LV_USE_TINY_TTF
+        return lv_tiny_ttf_create_file(path, size);
+#else
+        return NULL; // lvgl built without tiny_ttf
+#endif
+    }
+    return lv_font_load(path);
+}
+
+// glyphs missing from font are searched within fallback (builtin fonts are const and should never be patched)
+void gui_font_set_fallback(void *font, const void *fallback) {
+    ((lv_font_t*)font)->fallback = (const lv_font_t*)fallback;
+}
+
+void gui_obj_set_font(void *handle, const void *font) {
+    lv_obj_set_style_text_font((lv_obj_t*)handle, (const lv_font_t*)font, 0);
+}
+
+const void *gui_obj_get_font(void *handle) {
+    return lv_obj_get_style_text_font((lv_obj_t*)handle, 0);
+}
+
+// runtime images: '.png' through lodepng (LV_USE_PNG), anything else as lvgl binary image (header+data)
+static lv_img_dsc_t *gui_img_alloc(lv_img_cf_t cf, uint32_t width, uint32_t height, uint32_t size) {
+    lv_img_dsc_t *img = calloc(1, sizeof(lv_img_dsc_t));
+    uint8_t *data = malloc(size);
+    if (!img || !data) {
+        free(img);
+        free(data);
+        return NULL;
+    }
+    img->header.cf = cf;
+    img->header.w = width;
+    img->header.h = height;
+    img->data_size = size;
+    img->data = data;
+    return img;
+}
+
+#if LV_USE_PNG
+// lodepng returns RGBA8888, convert to lvgl true color + alpha (same as lv_png decoder)
+static lv_img_dsc_t *gui_img_load_png(const char *path) {
+    unsigned char *rgba = NULL;
+    unsigned width, height;
+    if (lodepng_decode32_file(&rgba, &width, &height, path) != 0) return NULL;
+
+    lv_img_dsc_t *img = gui_img_alloc(LV_IMG_CF_TRUE_COLOR_ALPHA, width, height, width * height * LV_IMG_PX_SIZE_ALPHA_BYTE);
+    if (img) {
+        uint8_t *data = (uint8_t*)img->data;
+        for (uint32_t idx = 0; idx < width * height; idx++) {
+            uint8_t *px = &rgba[idx * 4];
+            lv_color_t color = lv_color_make(px[0], px[1], px[2]);
+#if LV_COLOR_DEPTH == 32
+            data[idx * 4 + 0] = color.ch.blue;
+            data[idx * 4 + 1] = color.ch.green;
+            data[idx * 4 + 2] = color.ch.red;
+            data[idx * 4 + 3] = px[3];
+#elif LV_COLOR_DEPTH == 16
+            data[idx * 3 + 0] = color.full & 0xFF;
+            data[idx * 3 + 1] = color.full >> 8;
+            data[idx * 3 + 2] = px[3];
+#else
+            data[idx * 2 + 0] = color.full;
+            data[idx * 2 + 1] = px[3];
+#endif
+        }
+    }
+    free(rgba);
+    return img;
+}
+#endif
+
+// lvgl .bin is a raw header followed by pixels, reject anything lvgl would read past
+static lv_img_dsc_t *gui_img_load_bin(const char *path) {
+    FILE *file = fopen(path, "rb");
+    if (!file) return NULL;
+
+    lv_img_header_t header;
+    lv_img_dsc_t *img = NULL;
+    if (fread(&header, sizeof(header), 1, file) == 1 && fseek(file, 0, SEEK_END) == 0) {
+        long size = ftell(file) - (long)sizeof(header);
+        // unknown color formats have no defined size
+        uint32_t expected = (header.always_zero == 0 && header.w > 0 && header.h > 0)
+            ? lv_img_buf_get_img_size(header.w, header.h, header.cf) : 0;
+        if (expected > 0 && size == (long)expected && fseek(file, sizeof(header), SEEK_SET) == 0) {
+            img = gui_img_alloc(header.cf, header.w, header.h, size);
+            if (img && fread((uint8_t*)img->data, size, 1, file) != 1) {
+                free((uint8_t*)img->data);
+                free(img);
+                img = NULL;
+            }
+        }
+    }
+    fclose(file);
+    return img;
+}
+
+void *gui_img_load(const char *path) {
+    const char *ext = strrchr(path, '.');
+    if (ext && strcasecmp(ext, ".png") == 0) {
+#if LV_USE_PNG
+        return gui_img_load_png(path);
+#else
+        return NULL; // lvgl built without png support
+#endif
+    }
+    return gui_img_load_bin(path);
+}
+
+// chart widget (line mode, no point markers)
+void *gui_chart_create(void *parent, lv_coord_t x_ofs, lv_coord_t y_ofs) {
+    lv_obj_t *chart = lv_chart_create((lv_obj_t*)parent);
+    lv_obj_set_pos(chart, x_ofs, y_ofs);
+    lv_chart_set_type(chart, LV_CHART_TYPE_LINE);
+    lv_chart_set_update_mode(chart, LV_CHART_UPDATE_MODE_SHIFT);
+    lv_obj_set_style_size(chart, 0, LV_PART_INDICATOR);
+    return chart;
+}
+
+void gui_chart_set_size(void *chart, lv_coord_t width, lv_coord_t height) {
+    lv_obj_set_size((lv_obj_t*)chart, width, height);
+}
+
+void gui_chart_set_range(void *chart, bool secondary, lv_coord_t min, lv_coord_t max) {
+    lv_chart_axis_t axis = secondary ? LV_CHART_AXIS_SECONDARY_Y : LV_CHART_AXIS_PRIMARY_Y;
+    lv_chart_set_range((lv_obj_t*)chart, axis, min, max);
+}
+
+void *gui_chart_add_series(void *chart, uint8_t red, uint8_t green, uint8_t blue, bool secondary) {
+    lv_chart_axis_t axis = secondary ? LV_CHART_AXIS_SECONDARY_Y : LV_CHART_AXIS_PRIMARY_Y;
+    return lv_chart_add_series((lv_obj_t*)chart, lv_color_make(red, green, blue), axis);
+}
+
+// resize the chart and reload every series point in one shot
+void gui_chart_set_count(void *chart, uint16_t count) {
+    lv_chart_set_point_count((lv_obj_t*)chart, count);
+}
+
+void gui_chart_set_points(void *chart, void *series, const lv_coord_t *values, uint16_t count) {
+    for (uint16_t idx = 0; idx < count; idx++) {
+        lv_chart_set_value_by_id((lv_obj_t*)chart, (lv_chart_series_t*)series, idx, values[idx]);
+    }
+    lv_chart_refresh((lv_obj_t*)chart);
+}
+
+// periodic callback executed from lvgl loop thread
+typedef void (*gui_timer_cb_t)(void *context);
+
+typedef struct {
+    gui_timer_cb_t callback;
+    void *context;
+} gui_timer_ctx_t;
+
+static void gui_timer_handler(lv_timer_t *timer) {
+    gui_timer_ctx_t *ctx = (gui_timer_ctx_t*)timer->user_data;
+    ctx->callback(ctx->context);
+}
+
+// return NULL when out of memory
+void *gui_timer_create(gui_timer_cb_t callback, void *context, uint32_t period) {
+    gui_timer_ctx_t *ctx = malloc(sizeof(gui_timer_ctx_t));
+    if (!ctx) return NULL;
+    ctx->callback = callback;
+    ctx->context = context;
+    lv_timer_t *timer = lv_timer_create(gui_timer_handler, period, ctx);
+    if (!timer) free(ctx);
+    return timer;
+}
+
+// press gestures bypass lvgl-rclib event translation, code is 0:pressed 1:short click 2:long press
+typedef void (*gui_press_cb_t)(void *context, uint8_t code);
+
+typedef struct {
+    gui_press_cb_t callback;
+    void *context;
+} gui_press_ctx_t;
+
+static void gui_press_handler(lv_event_t *event) {
+    gui_press_ctx_t *ctx = (gui_press_ctx_t*)lv_event_get_user_data(event);
+    switch (lv_event_get_code(event)) {
+        case LV_EVENT_PRESSED:
+            ctx->callback(ctx->context, 0);
+            break;
+        // clicked is also sent after a long press, short clicked is not
+        case LV_EVENT_SHORT_CLICKED:
+            ctx->callback(ctx->context, 1);
+            break;
+        case LV_EVENT_LONG_PRESSED:
+            ctx->callback(ctx->context, 2);
+            break;
+        default:
+            break;
+    }
+}
+
+// return false when out of memory
+bool gui_obj_on_press(void *handle, gui_press_cb_t callback, void *context) {
+    gui_press_ctx_t *ctx = malloc(sizeof(gui_press_ctx_t));
+    if (!ctx) return false;
+    ctx->callback = callback;
+    ctx->context = context;
+    lv_obj_add_flag((lv_obj_t*)handle, LV_OBJ_FLAG_CLICKABLE);
+    lv_obj_add_event_cb((lv_obj_t*)handle, gui_press_handler, LV_EVENT_ALL, ctx);
+    return true;
+}
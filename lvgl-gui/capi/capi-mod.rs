@@ -31,10 +31,14 @@ pub(crate) mod cglue {
     include!("_capi-map.rs");
 }
 
+// runtime image with the same name takes precedence over compiled asset
 macro_rules! impl_static_imgbin {
     ($label:ident, $imgbin:ident) => {
         pub fn $label() -> &'static LvglImgDsc {
-            unsafe { &cglue::$imgbin }
+            match AssetPixmap::get_runtime(stringify!($label)) {
+                Some(img) => img,
+                None => unsafe { &cglue::$imgbin },
+            }
         }
     }
 }
@@ -59,4 +63,4 @@ pub fn get_time(format: &str) -> Result<String,()> {
 */
 
 // export static img asset
-include!("../assets/@img-assets.rs");
+include!(concat!(env!("OUT_DIR"), "/@img-assets.rs"));
@@ -0,0 +1,125 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// images loaded from filesystem at startup, compiled AssetPixmap remains the fallback
+use crate::capi::cglue;
+use crate::capi::{AssetPixmap, IMG_ASSETS};
+use lvgl::prelude::*;
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::fs;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::OnceLock;
+
+// leaked C descriptor, never freed nor modified once loaded
+struct RuntimeImg(AtomicPtr<LvglImgDsc>);
+
+impl RuntimeImg {
+    fn new(img: &'static LvglImgDsc) -> Self {
+        RuntimeImg(AtomicPtr::new(img as *const LvglImgDsc as *mut LvglImgDsc))
+    }
+
+    fn get(&self) -> &'static LvglImgDsc {
+        unsafe { &*self.0.load(Ordering::Relaxed) }
+    }
+}
+
+static RUNTIME_ASSETS: OnceLock<HashMap<String, RuntimeImg>> = OnceLock::new();
+
+pub struct LvglImgFile {}
+
+impl LvglImgFile {
+    // '.png' requires lvgl built with LV_USE_PNG, other files should use lvgl binary image format
+    pub fn load(path: &str) -> Option<&'static LvglImgDsc> {
+        let cpath = CString::new(path).ok()?;
+        let handle = unsafe { cglue::gui_img_load(cpath.as_ptr()) };
+        if handle.is_null() {
+            None
+        } else {
+            Some(unsafe { &*(handle as *const LvglImgDsc) })
+        }
+    }
+}
+
+pub struct AssetLoadReport {
+    pub loaded: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+impl AssetPixmap {
+    // asset name is file stem ('nfc_on.png' overrides AssetPixmap::nfc_on), should be called once before drawing panel
+    pub fn load_dir(dir: &str) -> Result<AssetLoadReport, String> {
+        let entries = match fs::read_dir(dir) {
+            Ok(value) => value,
+            Err(error) => return Err(format!("fail to read asset dir:{} error:{}", dir, error)),
+        };
+
+        let mut assets = HashMap::new();
+        let mut report = AssetLoadReport {
+            loaded: Vec::new(),
+            failed: Vec::new(),
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = match (path.file_stem(), path.extension()) {
+                (Some(stem), Some(ext)) if ext == "png" || ext == "bin" => stem.to_string_lossy().to_string(),
+                _ => continue,
+            };
+            match LvglImgFile::load(&path.to_string_lossy()) {
+                Some(img) => {
+                    assets.insert(name.clone(), RuntimeImg::new(img));
+                    report.loaded.push(name);
+                }
+                None => report.failed.push(path.to_string_lossy().to_string()),
+            }
+        }
+
+        if RUNTIME_ASSETS.set(assets).is_err() {
+            return Err("runtime assets already loaded".to_string());
+        }
+        Ok(report)
+    }
+
+    pub fn get_runtime(name: &str) -> Option<&'static LvglImgDsc> {
+        match RUNTIME_ASSETS.get() {
+            Some(assets) => assets.get(name).map(|img| img.get()),
+            None => None,
+        }
+    }
+
+    // runtime image first, then compiled asset
+    pub fn by_name(name: &str) -> Option<&'static LvglImgDsc> {
+        match Self::get_runtime(name) {
+            Some(img) => Some(img),
+            None => IMG_ASSETS
+                .iter()
+                .find(|(uid, _)| *uid == name)
+                .map(|(_, asset)| asset()),
+        }
+    }
+
+    // compiled and runtime names, sorted without duplicates
+    pub fn list() -> Vec<String> {
+        let mut names: Vec<String> = IMG_ASSETS.iter().map(|(uid, _)| uid.to_string()).collect();
+        if let Some(assets) = RUNTIME_ASSETS.get() {
+            names.extend(assets.keys().cloned());
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+}
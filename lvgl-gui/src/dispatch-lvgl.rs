@@ -0,0 +1,78 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// widget mutations posted from binder threads and executed from lvgl loop thread
+use crate::capi::cglue;
+use std::collections::VecDeque;
+use std::ffi::c_void;
+use std::sync::Mutex;
+
+type LvglCommand = Box<dyn FnOnce() + Send>;
+
+pub struct LvglDispatch {
+    queue: Mutex<VecDeque<LvglCommand>>,
+}
+
+extern "C" fn dispatch_timer_cb(context: *mut c_void) {
+    let dispatch = unsafe { &*(context as *const LvglDispatch) };
+    dispatch.execute();
+}
+
+impl LvglDispatch {
+    pub fn new() -> &'static Self {
+        let dispatch = LvglDispatch {
+            queue: Mutex::new(VecDeque::new()),
+        };
+        Box::leak(Box::new(dispatch))
+    }
+
+    // lvgl timer should be created before lvgl loop starts
+    pub(crate) fn start(&'static self, period: u32) -> Result<(), String> {
+        let timer = unsafe {
+            cglue::gui_timer_create(
+                Some(dispatch_timer_cb),
+                self as *const LvglDispatch as *mut c_void,
+                period,
+            )
+        };
+        if timer.is_null() {
+            Err("fail to create lvgl dispatch timer".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn post<F>(&self, command: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        if let Ok(mut queue) = self.queue.lock() {
+            queue.push_back(Box::new(command));
+        }
+    }
+
+    // queue is released before execution, commands may post new ones
+    fn execute(&self) {
+        let commands: Vec<LvglCommand> = match self.queue.lock() {
+            Ok(mut queue) => queue.drain(..).collect(),
+            Err(_) => return,
+        };
+        for command in commands {
+            command();
+        }
+    }
+}
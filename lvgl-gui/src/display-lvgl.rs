@@ -15,14 +15,18 @@
  * limitations under the License.
  */
 
+use crate::capi::cglue;
 use crate::prelude::*;
 use lvgl::prelude::*;
 use std::any::Any;
+use std::ffi::c_void;
 
 pub struct DisplayHandle {
     handle: LvglHandle,
     panel: Vec<&'static LvglWidget>,
+    custom: Vec<&'static dyn GuiWidget>,
     ctrlbox: Option<*mut dyn LvglHandler>,
+    dispatch: &'static LvglDispatch,
 }
 
 impl DisplayHandle {
@@ -32,7 +36,9 @@ impl DisplayHandle {
         let display = DisplayHandle {
             handle,
             panel: Vec::new(),
+            custom: Vec::new(),
             ctrlbox: None,
+            dispatch: LvglDispatch::new(),
         };
         display
     }
@@ -45,9 +51,37 @@ impl DisplayHandle {
         font: &LvglFont,
     ) -> &mut Self {
         self.handle.set_theme(primary, secondary, dark, font);
+        // widgets already drawn keep their theme styles until refreshed
+        unsafe { cglue::gui_theme_refresh() };
         self
     }
 
+    // theme switch once lvgl loop is started, should be called from lvgl thread (dispatch)
+    pub fn switch_theme(primary: (u8, u8, u8), secondary: (u8, u8, u8), dark: bool, font: &'static LvglFont) {
+        unsafe {
+            cglue::gui_theme_set(
+                primary.0,
+                primary.1,
+                primary.2,
+                secondary.0,
+                secondary.1,
+                secondary.2,
+                dark,
+                font as *const LvglFont as *const c_void,
+            )
+        };
+    }
+
+    // read from binding timers, a torn value only delays screensaver by one tick
+    pub fn get_inactive_time() -> u32 {
+        unsafe { cglue::gui_inactive_time() }
+    }
+
+    // should be called from lvgl thread (dispatch)
+    pub fn set_refresh_pause(pause: bool) {
+        unsafe { cglue::gui_refresh_pause(pause) };
+    }
+
     pub fn set_callback(&mut self, ctrlbox: Box<dyn LvglHandler>) -> &mut Self {
         self.ctrlbox = Some(Box::leak(ctrlbox));
         self
@@ -57,6 +91,11 @@ impl DisplayHandle {
         &self.panel
     }
 
+    // widgets should only be updated through dispatch once lvgl loop is started
+    pub fn get_dispatch(&self) -> &'static LvglDispatch {
+        self.dispatch
+    }
+
     pub fn get_root(&self) -> &'static LvglWidget {
         self.handle.get_root_widget()
     }
@@ -67,7 +106,10 @@ impl DisplayHandle {
             .binary_search_by(|widget| widget.get_uid().cmp(uid))
         {
             Ok(index) => self.panel[index].as_any(),
-            Err(_) => &0, // return a dummy value
+            Err(_) => match self.custom.iter().find(|widget| widget.get_uid() == uid) {
+                Some(widget) => widget.as_any(),
+                None => &0, // return a dummy value
+            },
         };
         widget
     }
@@ -89,6 +131,12 @@ impl DisplayHandle {
         let pixmap_time_x_ofs = 575;
         let label_time_x_ofs = 600;
 
+        let pixmap_cost_x_ofs = 200;
+        let label_cost_x_ofs = 222;
+
+        let pixmap_session_x_ofs = 690;
+        let label_session_x_ofs = 712;
+
         let label_date_height = 20;
 
         //-----------------------------------------
@@ -167,6 +215,56 @@ impl DisplayHandle {
             .set_value("17:20:25")
             .finalize(),
         );
+
+        self.panel.push(
+            LvglPixmap::new(
+                root,
+                "Pixmap-session-cost",
+                AssetPixmap::currency_exchange(),
+                pixmap_cost_x_ofs,
+                pixmap_date_time_ico_y_ofs+2,
+            )
+            .set_info("Pixmap session cost")
+            .finalize(),
+        );
+
+        self.panel.push(
+            LvglLabel::new(
+                root,
+                "session-cost",
+                LvglMkFont::std_18(),
+                label_cost_x_ofs,
+                pixmap_date_time_ico_y_ofs,
+            )
+            .set_height(label_time_height)
+            .set_value("--")
+            .finalize(),
+        );
+
+        self.panel.push(
+            LvglPixmap::new(
+                root,
+                "Pixmap-session-time",
+                AssetPixmap::stopwatch(),
+                pixmap_session_x_ofs,
+                pixmap_date_time_ico_y_ofs+2,
+            )
+            .set_info("Pixmap session time")
+            .finalize(),
+        );
+
+        self.panel.push(
+            LvglLabel::new(
+                root,
+                "session-time",
+                LvglMkFont::std_18(),
+                label_session_x_ofs,
+                pixmap_date_time_ico_y_ofs,
+            )
+            .set_height(label_time_height)
+            .set_value("00:00:00")
+            .finalize(),
+        );
         //-----------------------------------------
 
         let pixmap_ico_y_ofs = 15;
@@ -243,6 +341,32 @@ impl DisplayHandle {
         let pix_auth_status_x_ofs = 750;
         let pix_charge_status_x_ofs = 400;
 
+        // reservation info between plug and charge status
+        let label_reservation_x_ofs = 235;
+        let label_reservation_y_ofs = 50;
+        let label_reservation_height = 35;
+
+        // smart charging limit between charge and auth status
+        let label_limit_x_ofs = 585;
+        let label_limit_y_ofs = 40;
+        let label_limit_height = 30;
+        let bar_limit_width = 150;
+        let bar_limit_height = 15;
+
+        // status text below each status pixmap, only filled in accessibility mode
+        let label_status_y_ofs = 125;
+        let label_status_height = 20;
+
+        // accepted badge details below auth status
+        let label_auth_x_ofs = pix_auth_status_x_ofs;
+        let label_auth_y_ofs = 145;
+        let label_auth_height = 20;
+
+        // grid headroom (optional Linky meter) right of auth status
+        let label_grid_x_ofs = 935;
+        let label_grid_y_ofs = 40;
+        let label_grid_height = 25;
+
         self.panel.push(
             LvglPixmap::new(
                 root,
@@ -280,6 +404,151 @@ impl DisplayHandle {
             .finalize(),
         );
 
+        self.panel.push(
+            LvglLabel::new(
+                root,
+                "ReservationStatus",
+                LvglMkFont::std_14(),
+                label_reservation_x_ofs,
+                label_reservation_y_ofs,
+            )
+            .set_height(label_reservation_height)
+            .set_value("")
+            .finalize(),
+        );
+
+        self.panel.push(
+            LvglLabel::new(
+                root,
+                "ReservationTag",
+                LvglMkFont::std_18(),
+                label_reservation_x_ofs,
+                label_reservation_y_ofs + label_reservation_height,
+            )
+            .set_height(label_reservation_height)
+            .set_value("")
+            .finalize(),
+        );
+
+        self.panel.push(
+            LvglLabel::new(
+                root,
+                "ReservationCountdown",
+                LvglMkFont::std_22(),
+                label_reservation_x_ofs,
+                label_reservation_y_ofs + 2 * label_reservation_height,
+            )
+            .set_height(label_reservation_height)
+            .set_value("")
+            .finalize(),
+        );
+
+        self.panel.push(
+            LvglLabel::new(
+                root,
+                "LimitReason",
+                LvglMkFont::std_14(),
+                label_limit_x_ofs,
+                label_limit_y_ofs,
+            )
+            .set_height(label_limit_height)
+            .set_value("")
+            .finalize(),
+        );
+
+        self.panel.push(
+            LvglBar::new(
+                root,
+                "LimitBar",
+                label_limit_x_ofs,
+                label_limit_y_ofs + label_limit_height,
+            )
+            .set_info("Current limit against max")
+            .set_size(bar_limit_width, bar_limit_height)
+            .set_range(0, 32)
+            .set_value(32)
+            .finalize(),
+        );
+
+        self.panel.push(
+            LvglLabel::new(
+                root,
+                "LimitVal",
+                LvglMkFont::std_18(),
+                label_limit_x_ofs,
+                label_limit_y_ofs + label_limit_height + bar_limit_height + 5,
+            )
+            .set_height(label_limit_height)
+            .set_value("")
+            .finalize(),
+        );
+
+        self.panel.push(
+            LvglLabel::new(
+                root,
+                "LimitRemaining",
+                LvglMkFont::std_18(),
+                label_limit_x_ofs,
+                label_limit_y_ofs + 2 * label_limit_height + bar_limit_height + 5,
+            )
+            .set_height(label_limit_height)
+            .set_value("")
+            .finalize(),
+        );
+
+        for (uid, x_ofs) in [
+            ("Text-connect-status", pix_connect_status_x_ofs),
+            ("Text-charge-status", pix_charge_status_x_ofs),
+            ("Text-auth-status", pix_auth_status_x_ofs),
+        ] {
+            self.panel.push(
+                LvglLabel::new(root, uid, LvglMkFont::std_14(), x_ofs, label_status_y_ofs)
+                    .set_height(label_status_height)
+                    .set_value("")
+                    .finalize(),
+            );
+        }
+
+        for (idx, &uid) in ["AuthTag", "AuthLimits", "AuthMode"].iter().enumerate() {
+            self.panel.push(
+                LvglLabel::new(
+                    root,
+                    uid,
+                    LvglMkFont::std_14(),
+                    label_auth_x_ofs,
+                    label_auth_y_ofs + idx as i16 * label_auth_height,
+                )
+                .set_height(label_auth_height)
+                .set_value("")
+                .finalize(),
+            );
+        }
+
+        self.panel.push(
+            LvglLabel::new(
+                root,
+                "GridTxt",
+                LvglMkFont::std_14(),
+                label_grid_x_ofs,
+                label_grid_y_ofs,
+            )
+            .set_height(label_grid_height)
+            .set_value("Grid")
+            .finalize(),
+        );
+
+        self.panel.push(
+            LvglLabel::new(
+                root,
+                "GridVal",
+                LvglMkFont::std_18(),
+                label_grid_x_ofs,
+                label_grid_y_ofs + label_grid_height,
+            )
+            .set_height(label_grid_height)
+            .set_value("--")
+            .finalize(),
+        );
 
         // self.panel.push(
         //     LvglPixButton::new(
@@ -305,12 +574,12 @@ impl DisplayHandle {
         self.panel.push(
             LvglPixmap::new(
                 root,
-                "Pixmap-lang",
+                "Pixmap-bat",
                 AssetPixmap::battery_charge_on(),
                 label_status_bat_x_ofs + 20,
                 label_status_bat_y_ofs,
             )
-            .set_info("Pixmap lang")
+            .set_info("Pixmap battery")
             .finalize(),
         );
 
@@ -634,7 +903,13 @@ impl DisplayHandle {
         let pixmap_logo_y_ofs = 0;
 
 
-        let label_zone_mess_height = 1024 - label_zone_mess_x_ofs - 10 - 200;
+        // message zone and chart share the width left between qrcode and logo
+        let label_zone_mess_height = (1024 - label_zone_mess_x_ofs - 10 - 200) / 2;
+
+        let chart_x_ofs = label_zone_mess_x_ofs + label_zone_mess_height + 10;
+        let chart_y_ofs = 5;
+        let chart_width = pixmap_logo_x_ofs - chart_x_ofs - 15;
+        let chart_height = bare_code_size;
 
         self.panel.push(
             LvglQrcode::new(
@@ -646,7 +921,8 @@ impl DisplayHandle {
                 5,
                 5,
             )
-            .set_value("WIFI:T:WPA;S:tuxevse_hotspot;P:valeocharger;")
+            // payload is provided by the binding from config
+            .set_value("tux-evse")
             .finalize(),
         );
 
@@ -677,6 +953,220 @@ impl DisplayHandle {
             .finalize(),
         );
 
+        // session power (primary axis in W) and current (secondary axis in 0.1A)
+        self.custom.push(
+            LvglChart::new(root, "Chart-power", chart_x_ofs, chart_y_ofs)
+                .set_info("Session power curve")
+                .set_size(chart_width, chart_height)
+                .set_range(false, 0, 22000)
+                .set_range(true, 0, 320)
+                .add_series(0xFF, 0x98, 0x00, false)
+                .add_series(0x21, 0x96, 0xF3, true)
+                .set_count(1)
+                .finalize(),
+        );
+
+        self
+    }
+
+    // end of session summary, hidden until PowerRequest::Stop
+    pub fn draw_overlay_summary(&mut self, root: &LvglWidget) -> &mut Self {
+        let label_title_x_ofs = 20;
+        let label_title_y_ofs = 10;
+
+        let label_txt_x_ofs = 40;
+        let label_val_x_ofs = label_txt_x_ofs + 260;
+        let label_height = 45;
+        let label_y_ofs = 60;
+
+        let qrcode_size = 250;
+        let qrcode_x_ofs = 1024 - qrcode_size - 60;
+        let qrcode_y_ofs = 50;
+
+        self.panel.push(
+            LvglLabel::new(
+                root,
+                "SummaryTitle",
+                LvglMkFont::std_22(),
+                label_title_x_ofs,
+                label_title_y_ofs,
+            )
+            .set_height(label_height)
+            .set_value("Charging completed")
+            .finalize(),
+        );
+
+        let rows = [
+            ("SummaryEnergyTxt", "Energy", "SummaryEnergyVal"),
+            ("SummaryDurationTxt", "Duration", "SummaryDurationVal"),
+            ("SummaryAvgPowerTxt", "Average power", "SummaryAvgPowerVal"),
+            ("SummaryPeakPowerTxt", "Peak power", "SummaryPeakPowerVal"),
+            ("SummaryCostTxt", "Cost", "SummaryCostVal"),
+            ("SummaryTagTxt", "Badge", "SummaryTagVal"),
+        ];
+
+        for (idx, &(txt_uid, text, val_uid)) in rows.iter().enumerate() {
+            let label_row_y_ofs = label_y_ofs + idx as i16 * label_height;
+            self.panel.push(
+                LvglLabel::new(
+                    root,
+                    txt_uid,
+                    LvglMkFont::std_22(),
+                    label_txt_x_ofs,
+                    label_row_y_ofs,
+                )
+                .set_height(label_height)
+                .set_value(text)
+                .finalize(),
+            );
+            self.panel.push(
+                LvglLabel::new(
+                    root,
+                    val_uid,
+                    LvglMkFont::std_22(),
+                    label_val_x_ofs,
+                    label_row_y_ofs,
+                )
+                .set_height(label_height)
+                .set_value("--")
+                .finalize(),
+            );
+        }
+
+        self.panel.push(
+            LvglQrcode::new(
+                root,
+                "SummaryQrcode",
+                LvglColor::rvb(255, 255, 255),
+                LvglColor::rvb(0, 0, 0),
+                qrcode_size,
+                qrcode_x_ofs,
+                qrcode_y_ofs,
+            )
+            .set_value("-")
+            .finalize(),
+        );
+
+        self
+    }
+
+    // fault screen, hidden until an error is reported
+    pub fn draw_overlay_fault(&mut self, root: &LvglWidget) -> &mut Self {
+        let label_title_x_ofs = 20;
+        let label_title_y_ofs = 10;
+
+        let label_txt_x_ofs = 40;
+        let label_val_x_ofs = label_txt_x_ofs + 160;
+        let label_height = 60;
+        let label_y_ofs = 80;
+
+        let qrcode_size = 250;
+        let qrcode_x_ofs = 1024 - qrcode_size - 60;
+        let qrcode_y_ofs = 50;
+
+        self.panel.push(
+            LvglLabel::new(
+                root,
+                "FaultTitle",
+                LvglMkFont::std_22(),
+                label_title_x_ofs,
+                label_title_y_ofs,
+            )
+            .set_height(label_height)
+            .set_value("Out of order")
+            .finalize(),
+        );
+
+        let rows = [
+            ("FaultCodeTxt", "Code", "FaultCodeVal"),
+            ("FaultMessageTxt", "Problem", "FaultMessageVal"),
+            ("FaultActionTxt", "Action", "FaultActionVal"),
+        ];
+
+        for (idx, &(txt_uid, text, val_uid)) in rows.iter().enumerate() {
+            let label_row_y_ofs = label_y_ofs + idx as i16 * label_height;
+            self.panel.push(
+                LvglLabel::new(
+                    root,
+                    txt_uid,
+                    LvglMkFont::std_22(),
+                    label_txt_x_ofs,
+                    label_row_y_ofs,
+                )
+                .set_height(label_height)
+                .set_value(text)
+                .finalize(),
+            );
+            self.panel.push(
+                LvglLabel::new(
+                    root,
+                    val_uid,
+                    LvglMkFont::std_22(),
+                    label_val_x_ofs,
+                    label_row_y_ofs,
+                )
+                .set_height(label_height)
+                .set_value("--")
+                .finalize(),
+            );
+        }
+
+        self.panel.push(
+            LvglQrcode::new(
+                root,
+                "FaultQrcode",
+                LvglColor::rvb(255, 255, 255),
+                LvglColor::rvb(0, 0, 0),
+                qrcode_size,
+                qrcode_x_ofs,
+                qrcode_y_ofs,
+            )
+            .set_value("-")
+            .finalize(),
+        );
+
+        self
+    }
+
+    // over-current warning banner, hidden until grid meter reports an overload
+    pub fn draw_overlay_overcurrent(&mut self, root: &LvglWidget) -> &mut Self {
+        self.panel.push(
+            LvglLabel::new(
+                root,
+                "OverCurrentMsg",
+                LvglMkFont::std_22(),
+                0,
+                0,
+            )
+            .set_size(1024, 50)
+            .set_color(LvglColor::rvb(0xFF, 0xFF, 0xFF))
+            .set_background(LvglColor::rvb(0xD3, 0x2F, 0x2F))
+            .set_value("Grid over-current: charging power is reduced")
+            .finalize(),
+        );
+        self
+    }
+
+    // idle carousel slides replace the whole top area
+    pub fn draw_overlay_carousel(&mut self, root: &LvglWidget) -> &mut Self {
+        self.panel.push(
+            LvglPixmap::new(root, "Pixmap-carousel", AssetPixmap::tux_evsex150(), 0, 0)
+                .set_info("Idle carousel slide")
+                .finalize(),
+        );
+        self
+    }
+
+    // screensaver, black full screen with a dim hint
+    pub fn draw_overlay_saver(&mut self, root: &LvglWidget) -> &mut Self {
+        self.panel.push(
+            LvglLabel::new(root, "SaverMsg", LvglMkFont::std_22(), 0, 0)
+                .set_size(1024, 600)
+                .set_color(LvglColor::rvb(0x40, 0x40, 0x40))
+                .set_background(LvglColor::rvb(0x00, 0x00, 0x00))
+                .set_value("Touch screen to start")
+                .finalize(),
+        );
         self
     }
 
@@ -717,11 +1207,68 @@ impl DisplayHandle {
         self.draw_panel_top(area_top);
         self.draw_panel_mid(area_mid);
         self.draw_panel_bot(area_bot);
-        
+
+        // overlays are created last to stay on top of the main panel
+        let area_carousel = LvglArea::new(self.get_root(), "Area Carousel", 0, area_top_posy)
+            .set_size(1024, area_top_sizey)
+            .set_padding(0, 0, 0, 0)
+            .finalize();
+        self.draw_overlay_carousel(area_carousel);
+        self.custom.push(
+            LvglOverlay::new("Overlay-carousel", area_carousel)
+                .set_info("Idle carousel")
+                .finalize(),
+        );
+
+        let area_summary = LvglArea::new(self.get_root(), "Area Summary", 0, area_top_posy)
+            .set_size(1024, area_top_sizey + area_mid_sizey)
+            .set_padding(0, 0, 0, 0)
+            .finalize();
+        self.draw_overlay_summary(area_summary);
+        self.custom.push(
+            LvglOverlay::new("Overlay-summary", area_summary)
+                .set_info("End of session summary")
+                .finalize(),
+        );
+
+        let area_fault = LvglArea::new(self.get_root(), "Area Fault", 0, area_top_posy)
+            .set_size(1024, area_top_sizey + area_mid_sizey)
+            .set_padding(0, 0, 0, 0)
+            .finalize();
+        self.draw_overlay_fault(area_fault);
+        self.custom.push(
+            LvglOverlay::new("Overlay-fault", area_fault)
+                .set_info("Fault screen")
+                .finalize(),
+        );
+
+        let area_overcurrent = LvglArea::new(self.get_root(), "Area OverCurrent", 0, area_top_posy)
+            .set_size(1024, 50)
+            .set_padding(0, 0, 0, 0)
+            .finalize();
+        self.draw_overlay_overcurrent(area_overcurrent);
+        self.custom.push(
+            LvglOverlay::new("Overlay-overcurrent", area_overcurrent)
+                .set_info("Grid over-current warning")
+                .finalize(),
+        );
+
+        let area_saver = LvglArea::new(self.get_root(), "Area Saver", 0, 0)
+            .set_size(1024, 600)
+            .set_padding(0, 0, 0, 0)
+            .finalize();
+        self.draw_overlay_saver(area_saver);
+        self.custom.push(
+            LvglOverlay::new("Overlay-saver", area_saver)
+                .set_info("Screensaver")
+                .finalize(),
+        );
+
         self
     }
 
-    pub fn finalize(&mut self) {
+    // fail when posted updates could not be scheduled on lvgl thread
+    pub fn finalize(&mut self) -> Result<(), String> {
         // sort widget by uid and add them to pannel pool
         self.panel.sort_by(|a, b| a.get_uid().cmp(&b.get_uid()));
         for widget in &self.panel {
@@ -730,7 +1277,10 @@ impl DisplayHandle {
                 None => {}
             }
         }
+        // posted widget updates are executed from lvgl thread
+        self.dispatch.start(20)?;
         // start lvgl main loop thread
         self.handle.start_loop();
+        Ok(())
     }
 }
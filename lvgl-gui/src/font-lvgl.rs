@@ -0,0 +1,55 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// fonts loaded from files, lvgl-rclib only exposes builtin montserrat sizes
+use crate::capi::cglue;
+use lvgl::prelude::*;
+use std::ffi::{c_void, CString};
+
+pub struct LvglFontFile {}
+
+impl LvglFontFile {
+    // '.ttf' requires lvgl built with LV_USE_TINY_TTF, other files should use lvgl binary font format
+    pub fn load(path: &str, size: u16) -> Option<&'static LvglFont> {
+        let cpath = CString::new(path).ok()?;
+        let handle = unsafe { cglue::gui_font_load(cpath.as_ptr(), size as i16) };
+        if handle.is_null() {
+            None
+        } else {
+            Some(unsafe { &*(handle as *const LvglFont) })
+        }
+    }
+
+    // only fonts returned by load() may be patched, builtin fonts live in read only memory
+    pub fn set_fallback(font: &'static LvglFont, fallback: &'static LvglFont) {
+        unsafe {
+            cglue::gui_font_set_fallback(
+                font as *const LvglFont as *mut c_void,
+                fallback as *const LvglFont as *const c_void,
+            )
+        };
+    }
+
+    // closest builtin size, last link of every fallback chain
+    pub fn builtin(size: u16) -> &'static LvglFont {
+        match size {
+            0..=15 => LvglMkFont::std_14(),
+            16..=19 => LvglMkFont::std_18(),
+            _ => LvglMkFont::std_22(),
+        }
+    }
+}
@@ -24,11 +24,31 @@ mod capi;
 #[path = "display-lvgl.rs"]
 mod display;
 
+#[path = "widget-lvgl.rs"]
+mod widget;
+
+#[path = "refresh-lvgl.rs"]
+mod refresh;
+
+#[path = "dispatch-lvgl.rs"]
+mod dispatch;
+
+#[path = "font-lvgl.rs"]
+mod font;
+
+#[path = "asset-lvgl.rs"]
+mod asset;
+
 
 
 pub mod prelude {
     //pub(crate) use crate::capi::*;
     pub use crate::capi::AssetPixmap;
     pub use crate::display::*;
+    pub use crate::widget::*;
+    pub use crate::refresh::*;
+    pub use crate::dispatch::*;
+    pub use crate::font::*;
+    pub use crate::asset::*;
     pub use lvgl::prelude::*;
 }
\ No newline at end of file
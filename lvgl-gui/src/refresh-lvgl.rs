@@ -0,0 +1,145 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// coalesce high rate label and widget updates, only the last value is drawn at flush time
+use lvgl::prelude::*;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// last value per label uid, generic on label handle to keep it free from lvgl
+struct LabelQueue<L> {
+    pending: HashMap<&'static str, (L, String)>,
+    drawn: HashMap<&'static str, String>,
+}
+
+impl<L> LabelQueue<L> {
+    fn new() -> Self {
+        LabelQueue {
+            pending: HashMap::new(),
+            drawn: HashMap::new(),
+        }
+    }
+
+    // value is dropped when already on screen, otherwise it replaces any pending one
+    fn push(&mut self, uid: &'static str, label: L, text: String) {
+        if self.drawn.get(uid) == Some(&text) {
+            self.pending.remove(uid);
+        } else {
+            self.pending.insert(uid, (label, text));
+        }
+    }
+
+    // pending values are considered as drawn once taken
+    fn take(&mut self) -> Vec<(L, String)> {
+        let mut values = Vec::with_capacity(self.pending.len());
+        for (uid, (label, text)) in self.pending.drain() {
+            self.drawn.insert(uid, text.clone());
+            values.push((label, text));
+        }
+        values
+    }
+}
+
+struct RefreshState {
+    labels: LabelQueue<&'static LvglLabel>,
+    redraw: HashMap<&'static str, Box<dyn FnOnce() + Send>>,
+}
+
+pub struct LvglRefresh {
+    data_set: Mutex<RefreshState>,
+}
+
+impl LvglRefresh {
+    pub fn new() -> &'static Self {
+        let refresh = LvglRefresh {
+            data_set: Mutex::new(RefreshState {
+                labels: LabelQueue::new(),
+                redraw: HashMap::new(),
+            }),
+        };
+        Box::leak(Box::new(refresh))
+    }
+
+    // value is dropped when already on screen, otherwise it replaces any pending one
+    pub fn set_label(&self, label: &'static LvglLabel, text: String) {
+        if let Ok(mut state) = self.data_set.lock() {
+            state.labels.push(label.get_uid(), label, text);
+        }
+    }
+
+    // widget redraw (chart reload) replacing any pending one for the same uid
+    pub fn set_redraw(&self, uid: &'static str, redraw: Box<dyn FnOnce() + Send>) {
+        if let Ok(mut state) = self.data_set.lock() {
+            state.redraw.insert(uid, redraw);
+        }
+    }
+
+    // called at max refresh rate, return the number of redrawn labels and widgets
+    pub fn flush(&self) -> usize {
+        let mut state = match self.data_set.lock() {
+            Ok(guard) => guard,
+            Err(_) => return 0,
+        };
+        let pending = state.labels.take();
+        for (label, text) in &pending {
+            label.set_value(text.as_str());
+        }
+        let redraw: Vec<Box<dyn FnOnce() + Send>> = state.redraw.drain().map(|(_, redraw)| redraw).collect();
+        drop(state);
+
+        // redraw may lock its own data, run it without holding refresh lock
+        let count = pending.len() + redraw.len();
+        for redraw in redraw {
+            redraw();
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_value_before_flush_wins() {
+        let mut queue = LabelQueue::new();
+        queue.push("power", 1, "1 kW".to_string());
+        queue.push("power", 1, "2 kW".to_string());
+        queue.push("power", 1, "3 kW".to_string());
+        queue.push("energy", 2, "5 kWh".to_string());
+
+        let mut values = queue.take();
+        values.sort();
+        assert_eq!(values, vec![(1, "3 kW".to_string()), (2, "5 kWh".to_string())]);
+        assert!(queue.take().is_empty());
+    }
+
+    #[test]
+    fn drawn_value_is_not_redrawn() {
+        let mut queue = LabelQueue::new();
+        queue.push("power", 1, "3 kW".to_string());
+        queue.take();
+
+        queue.push("power", 1, "3 kW".to_string());
+        assert!(queue.take().is_empty());
+
+        // value going back to the drawn one cancels the pending change
+        queue.push("power", 1, "4 kW".to_string());
+        queue.push("power", 1, "3 kW".to_string());
+        assert!(queue.take().is_empty());
+    }
+}
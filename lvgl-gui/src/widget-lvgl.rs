@@ -0,0 +1,298 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// widgets missing from lvgl-rclib, wrapped through capi-map.c
+use crate::capi::cglue;
+use lvgl::prelude::*;
+use std::any::Any;
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::Mutex;
+
+// custom widgets cannot live within LvglWidget enum, DisplayHandle keeps them in a separated pool
+pub trait GuiWidget {
+    fn get_uid(&self) -> &'static str;
+    fn get_info(&self) -> &'static str;
+    fn as_any(&self) -> &dyn Any;
+}
+
+// stale values are greyed out, lvgl-rclib widgets only expose their raw handle
+pub trait GuiDimmed {
+    fn set_dimmed(&self, dimmed: bool);
+}
+
+macro_rules! impl_gui_dimmed {
+    ($($widget:ty),*) => {
+        $(
+            impl GuiDimmed for $widget {
+                fn set_dimmed(&self, dimmed: bool) {
+                    unsafe { cglue::gui_obj_set_dimmed(self.get_handle() as *mut c_void, dimmed) };
+                }
+            }
+        )*
+    };
+}
+impl_gui_dimmed!(LvglLabel, LvglPixmap);
+
+// state icons may be recolored from config, None keeps asset colors
+pub trait GuiTint {
+    fn set_tint(&self, tint: Option<(u8, u8, u8)>);
+}
+
+impl GuiTint for LvglPixmap {
+    fn set_tint(&self, tint: Option<(u8, u8, u8)>) {
+        let (red, green, blue, opa) = match tint {
+            Some((red, green, blue)) => (red, green, blue, 255),
+            None => (0, 0, 0, 0),
+        };
+        unsafe { cglue::gui_obj_set_tint(self.get_handle() as *mut c_void, red, green, blue, opa) };
+    }
+}
+
+// runtime fonts replace the builtin one given at widget creation time
+pub trait GuiFont {
+    fn set_font(&self, font: &'static LvglFont);
+    fn get_font(&self) -> &'static LvglFont;
+}
+
+macro_rules! impl_gui_font {
+    ($($widget:ty),*) => {
+        $(
+            impl GuiFont for $widget {
+                fn set_font(&self, font: &'static LvglFont) {
+                    unsafe {
+                        cglue::gui_obj_set_font(
+                            self.get_handle() as *mut c_void,
+                            font as *const LvglFont as *const c_void,
+                        )
+                    };
+                }
+                fn get_font(&self) -> &'static LvglFont {
+                    unsafe { &*(cglue::gui_obj_get_font(self.get_handle() as *mut c_void) as *const LvglFont) }
+                }
+            }
+        )*
+    };
+}
+impl_gui_font!(LvglWidget, LvglLabel);
+
+// gestures lvgl-rclib event callback does not tell apart, reported from lvgl thread
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GuiPress {
+    Down,
+    Click,
+    Long,
+}
+
+pub trait GuiPressHandler: Sync {
+    fn pressed(&self, uid: &'static str, press: GuiPress);
+}
+
+struct GuiPressCtx {
+    uid: &'static str,
+    handler: &'static dyn GuiPressHandler,
+}
+
+extern "C" fn gui_press_cb(context: *mut c_void, code: u8) {
+    let ctx = unsafe { &*(context as *const GuiPressCtx) };
+    let press = match code {
+        0 => GuiPress::Down,
+        1 => GuiPress::Click,
+        _ => GuiPress::Long,
+    };
+    ctx.handler.pressed(ctx.uid, press);
+}
+
+// should be registered before lvgl loop starts, widget becomes clickable
+pub trait GuiPressable {
+    fn on_press(&self, handler: &'static dyn GuiPressHandler) -> Result<(), String>;
+}
+
+impl GuiPressable for LvglWidget {
+    fn on_press(&self, handler: &'static dyn GuiPressHandler) -> Result<(), String> {
+        let ctx = Box::leak(Box::new(GuiPressCtx {
+            uid: self.get_uid(),
+            handler,
+        }));
+        let done = unsafe {
+            cglue::gui_obj_on_press(
+                self.get_handle() as *mut c_void,
+                Some(gui_press_cb),
+                ctx as *const GuiPressCtx as *mut c_void,
+            )
+        };
+        if done {
+            Ok(())
+        } else {
+            Err(format!("fail to register press callback on:{}", self.get_uid()))
+        }
+    }
+}
+
+// lvgl handles are only dereferenced by lvgl calls, which should run from LvglDispatch commands
+pub struct LvglChart {
+    uid: &'static str,
+    info: Mutex<&'static str>,
+    handle: AtomicPtr<c_void>,
+    series: Mutex<Vec<AtomicPtr<c_void>>>,
+}
+
+impl LvglChart {
+    pub fn new(root: &LvglWidget, uid: &'static str, x_ofs: i16, y_ofs: i16) -> &'static Self {
+        let handle = unsafe {
+            cglue::gui_chart_create(root.get_handle() as *mut c_void, x_ofs, y_ofs)
+        };
+        let chart = LvglChart {
+            uid,
+            info: Mutex::new(""),
+            handle: AtomicPtr::new(handle),
+            series: Mutex::new(Vec::new()),
+        };
+        Box::leak(Box::new(chart))
+    }
+
+    pub fn set_info(&self, info: &'static str) -> &Self {
+        if let Ok(mut value) = self.info.lock() {
+            *value = info;
+        }
+        self
+    }
+
+    fn get_handle(&self) -> *mut c_void {
+        self.handle.load(Ordering::Relaxed)
+    }
+
+    pub fn set_size(&self, width: i16, height: i16) -> &Self {
+        unsafe { cglue::gui_chart_set_size(self.get_handle(), width, height) };
+        self
+    }
+
+    // secondary=true targets the right Y axis
+    pub fn set_range(&self, secondary: bool, min: i16, max: i16) -> &Self {
+        unsafe { cglue::gui_chart_set_range(self.get_handle(), secondary, min, max) };
+        self
+    }
+
+    // series index follows creation order
+    pub fn add_series(&self, red: u8, green: u8, blue: u8, secondary: bool) -> &Self {
+        let series =
+            unsafe { cglue::gui_chart_add_series(self.get_handle(), red, green, blue, secondary) };
+        if let Ok(mut value) = self.series.lock() {
+            value.push(AtomicPtr::new(series));
+        }
+        self
+    }
+
+    // all series share the same point count, resize before pushing values
+    pub fn set_count(&self, count: usize) -> &Self {
+        unsafe { cglue::gui_chart_set_count(self.get_handle(), count as u16) };
+        self
+    }
+
+    pub fn set_values(&self, series: usize, values: &[i16]) -> &Self {
+        let handle = match self.series.lock() {
+            Ok(value) => value.get(series).map(|handle| handle.load(Ordering::Relaxed)),
+            Err(_) => None,
+        };
+        if let Some(handle) = handle {
+            unsafe {
+                cglue::gui_chart_set_points(
+                    self.get_handle(),
+                    handle,
+                    values.as_ptr(),
+                    values.len() as u16,
+                )
+            };
+        }
+        self
+    }
+
+    pub fn finalize(&'static self) -> &'static Self {
+        self
+    }
+}
+
+impl GuiWidget for LvglChart {
+    fn get_uid(&self) -> &'static str {
+        self.uid
+    }
+    fn get_info(&self) -> &'static str {
+        match self.info.lock() {
+            Ok(value) => *value,
+            Err(_) => "",
+        }
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+// full area drawn on top of the panel and shown on demand (summary, fault, ...)
+pub struct LvglOverlay {
+    uid: &'static str,
+    info: Mutex<&'static str>,
+    area: &'static LvglWidget,
+}
+
+impl LvglOverlay {
+    // overlay is hidden at creation time
+    pub fn new(uid: &'static str, area: &'static LvglWidget) -> &'static Self {
+        let overlay = LvglOverlay {
+            uid,
+            info: Mutex::new(""),
+            area,
+        };
+        overlay.set_hidden(true);
+        Box::leak(Box::new(overlay))
+    }
+
+    pub fn set_info(&self, info: &'static str) -> &Self {
+        if let Ok(mut value) = self.info.lock() {
+            *value = info;
+        }
+        self
+    }
+
+    pub fn get_area(&self) -> &'static LvglWidget {
+        self.area
+    }
+
+    // lvgl call, should run from LvglDispatch commands once lvgl loop is started
+    pub fn set_hidden(&self, hidden: bool) -> &Self {
+        unsafe { cglue::gui_obj_set_hidden(self.area.get_handle() as *mut c_void, hidden) };
+        self
+    }
+
+    pub fn finalize(&'static self) -> &'static Self {
+        self
+    }
+}
+
+impl GuiWidget for LvglOverlay {
+    fn get_uid(&self) -> &'static str {
+        self.uid
+    }
+    fn get_info(&self) -> &'static str {
+        match self.info.lock() {
+            Ok(value) => *value,
+            Err(_) => "",
+        }
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}